@@ -0,0 +1,48 @@
+//! Timings of the hot paths against the [`MockBackend`], run on the host with
+//! `cargo bench --features std,mock`.
+//!
+//! Besides the module, the mock stands in for the coreinit functions the crate calls, so the
+//! bench links without the console libraries. It measures the crate, not the overlay.
+
+use notifications::{error, info, manager, mock::MockBackend};
+use std::{hint::black_box, time::Instant};
+
+const ITERATIONS: u32 = 10_000;
+
+fn bench(name: &str, mut f: impl FnMut(u32)) {
+    MockBackend::reset();
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        f(i);
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<24} {:>10.2?} total {:>10.2?} / iter",
+        elapsed,
+        elapsed / ITERATIONS
+    );
+}
+
+fn main() {
+    bench("builder", |i| {
+        black_box(info("Installing").field("step", i).timestamp(true));
+    });
+
+    bench("show info", |i| {
+        info("Installing").field("step", i).show().unwrap();
+    });
+
+    bench("show error", |_| {
+        error("Failed to connect to the server").show().unwrap();
+    });
+
+    bench("manager submit", |i| {
+        black_box(manager().submit(info("Installing").field("step", i)));
+    });
+
+    bench("manager update", |i| {
+        let mut manager = manager();
+        manager.submit(info("Installing").field("step", i));
+        manager.update().unwrap();
+    });
+}
@@ -0,0 +1,93 @@
+use crate::{Level, NotificationError, error, info, manager, policy::Sink};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Display;
+
+/// Collects the results of a batch operation and shows a single notification at the end,
+/// e.g. `"3 of 57 files failed - see log"` instead of one error per item.
+///
+/// ```ignore
+/// let mut report = BatchReport::new("files");
+/// for file in files {
+///     report.record(file.name(), &copy(file));
+/// }
+/// report.finish()?;
+/// ```
+pub struct BatchReport {
+    items: &'static str,
+    total: usize,
+    failures: Vec<String>,
+    sink: Option<Sink>,
+}
+
+impl BatchReport {
+    /// Report counting `items`, e.g. `"files"`.
+    pub fn new(items: &'static str) -> Self {
+        Self {
+            items,
+            total: 0,
+            failures: Vec::new(),
+            sink: None,
+        }
+    }
+
+    /// Receives every failure on [`finish`](Self::finish), the sink of the
+    /// [`NotificationManager`](crate::NotificationManager) by default.
+    pub fn sink(mut self, sink: Sink) -> Self {
+        self.sink = Some(sink);
+        self
+    }
+
+    /// Counts the result of `item`.
+    pub fn record<T, E: Display>(&mut self, item: impl Display, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self.succeeded(),
+            Err(e) => self.failed(item, e),
+        }
+    }
+
+    pub fn succeeded(&mut self) {
+        self.total += 1;
+    }
+
+    pub fn failed(&mut self, item: impl Display, error: impl Display) {
+        self.total += 1;
+        self.failures.push(format!("{item}: {error}"));
+    }
+
+    /// Failures recorded so far as `"item: error"`.
+    pub fn failures(&self) -> &[String] {
+        &self.failures
+    }
+
+    /// Shows the success or the summarized failures and passes every failure to the sink.
+    ///
+    /// Nothing is shown for an empty batch.
+    pub fn finish(self) -> Result<(), NotificationError> {
+        if self.total == 0 {
+            return Ok(());
+        }
+
+        if self.failures.is_empty() {
+            info(&format!("{} {} done", self.total, self.items)).show()?;
+            return Ok(());
+        }
+
+        let sink = self.sink.or_else(|| manager().sink());
+        if let Some(sink) = sink {
+            for failure in &self.failures {
+                sink(Level::Error, failure);
+            }
+        }
+
+        let text = match self.failures.len() {
+            failed if failed == self.total => format!("All {} {} failed", self.total, self.items),
+            failed => format!("{failed} of {} {} failed", self.total, self.items),
+        };
+        match sink {
+            Some(_) => error(&format!("{text} - see log")).show()?,
+            None => error(&text).show()?,
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use alloc::string::String;
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Ticks per second of the Wii U system timer (bus clock / 4).
+pub(crate) const TIMER_CLOCK: u128 = 248_625_000 / 4;
+
+/// Time is advanced by [`tick`](crate::tick) instead of the system timer.
+static TICKED: AtomicBool = AtomicBool::new(false);
+/// Time advanced by [`tick`](crate::tick) in nanoseconds.
+static ELAPSED: AtomicU64 = AtomicU64::new(0);
+
+/// Current time for throttles, timeouts and queues: the time advanced by
+/// [`tick`](crate::tick) in tick mode, otherwise the time since the console booted.
+pub(crate) fn now() -> Duration {
+    if TICKED.load(Ordering::Relaxed) {
+        return Duration::from_nanos(ELAPSED.load(Ordering::Relaxed));
+    }
+    real()
+}
+
+/// Switches to tick mode, continuing from the current time, and advances it by `delta`.
+pub(crate) fn advance(delta: Duration) {
+    if !TICKED.swap(true, Ordering::Relaxed) {
+        ELAPSED.store(real().as_nanos() as u64, Ordering::Relaxed);
+    }
+    ELAPSED.fetch_add(delta.as_nanos() as u64, Ordering::Relaxed);
+}
+
+/// Switches to tick mode at `time`.
+#[cfg(feature = "mock")]
+pub(crate) fn start_at(time: Duration) {
+    ELAPSED.store(time.as_nanos() as u64, Ordering::Relaxed);
+    TICKED.store(true, Ordering::Relaxed);
+}
+
+/// Returns to the system timer.
+pub(crate) fn untick() {
+    TICKED.store(false, Ordering::Relaxed);
+}
+
+/// Time elapsed since the console booted, also in tick mode.
+pub(crate) fn real() -> Duration {
+    let ticks = unsafe { wut::bindings::OSGetSystemTime() } as u128;
+    Duration::from_nanos((ticks * 1_000_000_000 / TIMER_CLOCK) as u64)
+}
+
+/// Appends the local wall-clock time as `"[hh:mm:ss] "`.
+pub(crate) fn timestamp(out: &mut String) {
+    let mut time = wut::bindings::OSCalendarTime::default();
+    unsafe { wut::bindings::OSTicksToCalendarTime(wut::bindings::OSGetTime(), &mut time) };
+
+    let _ = write!(
+        out,
+        "[{:02}:{:02}:{:02}] ",
+        time.tm_hour, time.tm_min, time.tm_sec
+    );
+}
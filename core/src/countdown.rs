@@ -0,0 +1,98 @@
+use crate::{Notification, NotificationError, clock, dynamic};
+use alloc::{boxed::Box, string::String};
+use core::{fmt::Write, time::Duration};
+
+/// Dynamic notification counting down the remaining seconds, e.g. `"Rebooting in {}…"`.
+///
+/// The text is updated by [`poll`](Self::poll) once the shown second changed, call it
+/// periodically (e.g. once per frame). The notification finishes once the time is up.
+pub struct Countdown {
+    parts: (&'static str, &'static str),
+    deadline: Duration,
+    shown: u64,
+    buffer: String,
+    on_complete: Option<Box<dyn FnOnce()>>,
+    notification: Option<Notification>,
+}
+
+impl Countdown {
+    /// Shows `template` with the first `{}` replaced by the remaining seconds.
+    pub fn new(duration: Duration, template: &'static str) -> Result<Self, NotificationError> {
+        let parts = template.split_once("{}").unwrap_or((template, ""));
+        let mut countdown = Self {
+            parts,
+            deadline: clock::now() + duration,
+            shown: 0,
+            buffer: String::new(),
+            on_complete: None,
+            notification: None,
+        };
+
+        let seconds = countdown.seconds();
+        countdown.render(seconds);
+        countdown.notification = Some(dynamic(&countdown.buffer).show()?);
+        countdown.shown = seconds;
+        Ok(countdown)
+    }
+
+    /// Called once the countdown finished, not if it is cancelled.
+    pub fn on_complete<F: 'static + FnOnce()>(mut self, f: F) -> Self {
+        self.on_complete = Some(Box::new(f));
+        self
+    }
+
+    /// Time until the countdown finishes.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_sub(clock::now())
+    }
+
+    /// Updates the text, returns `true` once the countdown finished.
+    ///
+    /// The completion callback runs once the time is up, also if finishing the notification
+    /// failed.
+    pub fn poll(&mut self) -> Result<bool, NotificationError> {
+        let Some(notification) = self.notification.take() else {
+            return Ok(true);
+        };
+
+        if self.remaining().is_zero() {
+            let result = notification.finish();
+            if let Some(f) = self.on_complete.take() {
+                f();
+            }
+            return result.map(|_| true);
+        }
+
+        let seconds = self.seconds();
+        if seconds != self.shown {
+            self.render(seconds);
+            self.shown = seconds;
+            let result = notification.text(&self.buffer);
+            self.notification = Some(notification);
+            result?;
+        } else {
+            self.notification = Some(notification);
+        }
+
+        Ok(false)
+    }
+
+    /// Stops counting and fades out the notification without calling the completion callback.
+    pub fn cancel(mut self) {
+        self.on_complete = None;
+        if let Some(notification) = self.notification.take() {
+            notification.dismiss();
+        }
+    }
+
+    /// Remaining seconds rounded up, so the last second shows `1`.
+    fn seconds(&self) -> u64 {
+        let remaining = self.remaining();
+        remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+    }
+
+    fn render(&mut self, seconds: u64) {
+        self.buffer.clear();
+        let _ = write!(self.buffer, "{}{seconds}{}", self.parts.0, self.parts.1);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{Level, info, manager, policy};
+use core::sync::atomic::{AtomicBool, Ordering};
+use notifications_sys as sys;
+
+static DEGRADED: AtomicBool = AtomicBool::new(false);
+
+/// Maximum number of characters shown in the degraded mode.
+pub(crate) const MAX_TEXT: usize = 64;
+
+const WARNING: &str = "Low memory, notifications are reduced";
+
+/// Whether the crate is in the degraded low memory mode.
+///
+/// The mode is entered when the module reports [`AllocationFailed`]. While degraded, texts are
+/// shortened to 64 characters and passed to the [`Sink`](crate::policy::Sink) in full, and the
+/// finish callbacks of info and error notifications aren't registered with the module, they run
+/// as soon as the notification is shown.
+///
+/// [`AllocationFailed`]: crate::NotificationError::AllocationFailed
+pub fn is_degraded() -> bool {
+    DEGRADED.load(Ordering::Relaxed)
+}
+
+/// Leaves the degraded mode, e.g. after memory was freed.
+pub fn recover() {
+    DEGRADED.store(false, Ordering::Relaxed);
+}
+
+/// Enters the degraded mode if the module ran out of memory and warns once.
+///
+/// The warning is passed to the sink and queued with the manager, which retries it until the
+/// module has memory to show it.
+pub(crate) fn observe(status: i32) {
+    if status != sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED {
+        return;
+    }
+
+    if DEGRADED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    if let Some(sink) = policy::sink() {
+        sink(Level::Warn, WARNING);
+    }
+    manager::submit(info(WARNING).into());
+}
+
+/// Passes `text` to the sink if it is shortened in degraded mode.
+pub(crate) fn overflow(level: Level, text: &str) {
+    if !is_degraded() || text.chars().nth(MAX_TEXT).is_none() {
+        return;
+    }
+
+    if let Some(sink) = policy::sink() {
+        sink(level, text);
+    }
+}
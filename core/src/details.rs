@@ -0,0 +1,133 @@
+use crate::{NotificationError, clock, degrade, error, info, signal::Signal};
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+use core::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+/// `VPAD_BUTTON_X`
+static BUTTON: AtomicU32 = AtomicU32::new(0x2000);
+
+/// Maximum number of characters per details page.
+const PAGE: usize = 80;
+
+/// VPAD button mask requesting the details of an [`error_with_details`], `VPAD_BUTTON_X` by
+/// default.
+pub fn set_details_button(mask: u32) {
+    BUTTON.store(mask, Ordering::Relaxed);
+}
+
+/// Shows `summary` as error, the details are shown as paginated info notifications if the
+/// [details button](set_details_button) is pressed while the error is visible.
+///
+/// Pass the buttons read with `VPADRead` to [`Details::poll`] every frame. In degraded mode the
+/// module doesn't report when the error faded out, it counts as visible for its duration.
+pub fn error_with_details(summary: &str, details: &str) -> Result<Details, NotificationError> {
+    let visible = Arc::new(Signal::default());
+    let builder = error(summary);
+    let until = degrade::is_degraded().then(|| clock::now() + builder.duration);
+    match until {
+        Some(_) => builder.show()?,
+        None => {
+            let finished = visible.clone();
+            builder.callback(move || finished.set()).show()?
+        }
+    };
+
+    Ok(Details {
+        pages: paginate(details),
+        finished: visible,
+        until,
+        shown: false,
+    })
+}
+
+/// Pending details of an [`error_with_details`].
+pub struct Details {
+    pages: Vec<String>,
+    finished: Arc<Signal>,
+    /// End of the error in degraded mode, which doesn't report it.
+    until: Option<Duration>,
+    shown: bool,
+}
+
+impl Details {
+    /// Shows the details if `buttons` contain the details button and the error is still
+    /// visible, returns whether they were shown.
+    ///
+    /// The first page is shown immediately, the following ones are chained with
+    /// [`then`](crate::NotificationBuilder::then) and need
+    /// [`NotificationManager::update`](crate::NotificationManager::update).
+    pub fn poll(&mut self, buttons: u32) -> Result<bool, NotificationError> {
+        if self.shown || self.is_finished() || buttons & BUTTON.load(Ordering::Relaxed) == 0 {
+            return Ok(false);
+        }
+        self.shown = true;
+
+        let count = self.pages.len();
+        let mut pages = self.pages.iter().enumerate().rev().map(|(i, page)| {
+            info(&match count {
+                1 => page.clone(),
+                _ => format!("{page} ({}/{count})", i + 1),
+            })
+        });
+        if let Some(last) = pages.next() {
+            pages.fold(last, |next, page| page.then(next)).show()?;
+        }
+
+        Ok(true)
+    }
+
+    /// The error finished without the details being requested.
+    pub fn is_expired(&self) -> bool {
+        !self.shown && self.is_finished()
+    }
+
+    fn is_finished(&self) -> bool {
+        match self.until {
+            Some(until) => clock::now() >= until,
+            None => self.finished.is_set(),
+        }
+    }
+}
+
+/// Splits `text` into pages of up to [`PAGE`] characters at whitespace. Words longer than a
+/// page are kept whole on a page of their own.
+fn paginate(text: &str) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut page = String::new();
+    for word in text.split_whitespace() {
+        let len = page.chars().count();
+        if len > 0 && len + 1 + word.chars().count() > PAGE {
+            pages.push(core::mem::take(&mut page));
+        }
+        if !page.is_empty() {
+            page.push(' ');
+        }
+        page.push_str(word);
+    }
+    if !page.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages() {
+        assert!(paginate("  ").is_empty());
+        assert_eq!(paginate("a  b\nc"), ["a b c"]);
+
+        let text = "word ".repeat(40);
+        let pages = paginate(&text);
+        assert_eq!(pages.len(), 3);
+        assert!(pages.iter().all(|page| page.chars().count() <= PAGE));
+        assert_eq!(pages.join(" "), text.trim_end());
+
+        let long = "x".repeat(PAGE + 1);
+        assert_eq!(paginate(&format!("a {long} b")), ["a", &*long, "b"]);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    Level, NotificationError, clock, error, info,
+    logging::{Severity, level_map},
+};
+use alloc::collections::VecDeque;
+use core::time::Duration;
+
+/// Escalates repeated reports of the same condition, replacing hand-rolled "warn three times,
+/// then fail" logic.
+///
+/// Reports within the window are shown as quiet info, then as warning and finally as shaking
+/// error notifications. Warnings are shown according to the
+/// [`LevelMap`](crate::logging::LevelMap).
+pub struct Escalator {
+    tag: &'static str,
+    window: Duration,
+    warn_after: usize,
+    error_after: usize,
+    reports: VecDeque<Duration>,
+}
+
+impl Escalator {
+    /// Warns on the 2nd and fails on the 3rd report within a minute.
+    pub fn new(tag: &'static str) -> Self {
+        Self {
+            tag,
+            window: Duration::from_secs(60),
+            warn_after: 2,
+            error_after: 3,
+            reports: VecDeque::new(),
+        }
+    }
+
+    /// Time in which reports are counted together.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Number of reports within the window from which on warnings and errors are shown.
+    pub fn thresholds(mut self, warn_after: usize, error_after: usize) -> Self {
+        self.warn_after = warn_after;
+        self.error_after = error_after.max(warn_after);
+        self
+    }
+
+    /// Shows `text` with the level reached by the reports within the window.
+    pub fn report(&mut self, text: &str) -> Result<Level, NotificationError> {
+        let now = clock::now();
+        while self
+            .reports
+            .front()
+            .is_some_and(|&t| now.saturating_sub(t) > self.window)
+        {
+            self.reports.pop_front();
+        }
+        self.reports.push_back(now);
+
+        let count = self.reports.len();
+        let level = if count >= self.error_after {
+            Level::Error
+        } else if count >= self.warn_after {
+            Level::Warn
+        } else {
+            Level::Info
+        };
+
+        match level {
+            Level::Info => {
+                info(text).tag(self.tag).show()?;
+            }
+            Level::Warn => {
+                let builder = info(text).tag(self.tag);
+                if let Some(spec) = level_map().action(Severity::Warn).apply(builder, None) {
+                    spec.show()?;
+                }
+            }
+            Level::Error => {
+                error(text).tag(self.tag).show()?;
+            }
+        }
+
+        Ok(level)
+    }
+
+    /// Forgets all reports, e.g. once the condition resolved.
+    pub fn reset(&mut self) {
+        self.reports.clear();
+    }
+}
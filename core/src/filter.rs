@@ -0,0 +1,361 @@
+//! Ordered chain of text filters applied before the text reaches the overlay.
+//!
+//! Prefix, timestamp, thread name, fields and location are composed first, the chain then
+//! processes the whole text. The default chain renders markup, handles newlines, replaces
+//! unsupported glyphs and truncates in degraded mode; replace it with [`set_filters`] or per
+//! notification with [`filters`](crate::NotificationBuilder::filters) to add or reorder steps.
+//!
+//! ```ignore
+//! static FILTERS: [&dyn TextFilter; 3] = [&Profanity, &NewlineFilter, &GlyphFilter];
+//! filter::set_filters(&FILTERS);
+//! ```
+
+use crate::{Theme, degrade, text, theme};
+use alloc::{borrow::Cow, string::String};
+use spin::Mutex;
+
+/// Chain of filters applied in order.
+pub type Filters = &'static [&'static dyn TextFilter];
+
+/// Markup, newlines, glyphs and degraded truncation.
+pub static DEFAULT_FILTERS: [&dyn TextFilter; 4] =
+    [&MarkupFilter, &NewlineFilter, &GlyphFilter, &DegradeFilter];
+
+static FILTERS: Mutex<Filters> = Mutex::new(&DEFAULT_FILTERS);
+
+/// Step of the text preprocessing.
+pub trait TextFilter: Send + Sync {
+    /// Returns the filtered text, borrowed texts should be passed through if unchanged.
+    fn filter<'a>(&self, text: Cow<'a, str>, theme: &Theme) -> Cow<'a, str>;
+}
+
+/// Replaces the global chain.
+pub fn set_filters(filters: Filters) {
+    *FILTERS.lock() = filters;
+}
+
+/// Currently active global chain.
+pub fn filters() -> Filters {
+    *FILTERS.lock()
+}
+
+/// Global chain, `None` while it is locked, e.g. by the code which panicked.
+pub(crate) fn try_filters() -> Option<Filters> {
+    FILTERS.try_lock().map(|filters| *filters)
+}
+
+/// Runs `text` through `filters`, or the global chain if `None`.
+pub(crate) fn apply<'a>(text: Cow<'a, str>, filters: Option<Filters>) -> Cow<'a, str> {
+    apply_with(text, filters.unwrap_or_else(self::filters), &theme())
+}
+
+/// Runs `text` through `filters` styled by `theme`.
+pub(crate) fn apply_with<'a>(text: Cow<'a, str>, filters: Filters, theme: &Theme) -> Cow<'a, str> {
+    filters
+        .iter()
+        .fold(text, |text, filter| filter.filter(text, theme))
+}
+
+/// Applies `f`, keeping `text` if `f` returned it unchanged.
+pub(crate) fn map<'a>(text: Cow<'a, str>, f: impl FnOnce(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    let changed = match f(&text) {
+        Cow::Owned(changed) => Some(changed),
+        Cow::Borrowed(_) => None,
+    };
+    changed.map_or(text, Cow::Owned)
+}
+
+/// Renders `*emphasis*` and `` `code` `` according to [`Theme::markup`].
+pub struct MarkupFilter;
+
+impl TextFilter for MarkupFilter {
+    fn filter<'a>(&self, text: Cow<'a, str>, theme: &Theme) -> Cow<'a, str> {
+        map(text, |t| text::markup(t, theme.markup))
+    }
+}
+
+/// Handles line breaks according to [`Theme::newlines`].
+pub struct NewlineFilter;
+
+impl TextFilter for NewlineFilter {
+    fn filter<'a>(&self, text: Cow<'a, str>, theme: &Theme) -> Cow<'a, str> {
+        map(text, |t| text::sanitize(t, theme.newlines))
+    }
+}
+
+/// Replaces characters the font can't render according to [`Theme::glyphs`].
+pub struct GlyphFilter;
+
+impl TextFilter for GlyphFilter {
+    fn filter<'a>(&self, text: Cow<'a, str>, theme: &Theme) -> Cow<'a, str> {
+        map(text, |t| text::fallback(t, theme.glyphs.as_ref()))
+    }
+}
+
+/// Truncates long texts in [degraded](crate::degrade) mode.
+pub struct DegradeFilter;
+
+impl TextFilter for DegradeFilter {
+    fn filter<'a>(&self, text: Cow<'a, str>, _theme: &Theme) -> Cow<'a, str> {
+        if !degrade::is_degraded() {
+            return text;
+        }
+        match text.char_indices().nth(degrade::MAX_TEXT) {
+            Some((index, _)) => {
+                let mut truncated = String::from(&text[..index]);
+                truncated.push('\u{2026}');
+                Cow::Owned(truncated)
+            }
+            None => text,
+        }
+    }
+}
+
+// region: Redact
+
+/// Secret masked by [`Redact`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    /// Exact text, e.g. an account name.
+    Literal(&'static str),
+    /// Dotted IPv4 addresses.
+    Ipv4,
+    /// Text following a key up to the next whitespace, e.g. `"token="`. The key is kept.
+    After(&'static str),
+    /// Runs of at least this many digits, e.g. serial numbers.
+    Digits(usize),
+}
+
+/// IP addresses, tokens, passwords and serial numbers.
+pub static DEFAULT_SECRETS: [Secret; 5] = [
+    Secret::Ipv4,
+    Secret::After("token="),
+    Secret::After("key="),
+    Secret::After("password="),
+    Secret::Digits(9),
+];
+
+/// Masks secrets before the text reaches the overlay, the history or a sink, so they don't leak
+/// in streams and screenshots.
+///
+/// Place it first in the chain:
+///
+/// ```ignore
+/// static REDACT: Redact = Redact::new(&DEFAULT_SECRETS);
+/// static FILTERS: [&dyn TextFilter; 5] =
+///     [&REDACT, &MarkupFilter, &NewlineFilter, &GlyphFilter, &DegradeFilter];
+/// ```
+pub struct Redact {
+    secrets: &'static [Secret],
+    mask: &'static str,
+}
+
+impl Redact {
+    /// Replaces `secrets` with `"***"`.
+    pub const fn new(secrets: &'static [Secret]) -> Self {
+        Self {
+            secrets,
+            mask: "***",
+        }
+    }
+
+    pub const fn mask(mut self, mask: &'static str) -> Self {
+        self.mask = mask;
+        self
+    }
+}
+
+impl TextFilter for Redact {
+    fn filter<'a>(&self, text: Cow<'a, str>, _theme: &Theme) -> Cow<'a, str> {
+        self.secrets.iter().fold(text, |text, secret| {
+            map(text, |t| redact(t, *secret, self.mask))
+        })
+    }
+}
+
+/// Replaces every occurrence of `secret` in `text` with `mask`.
+fn redact<'a>(text: &'a str, secret: Secret, mask: &str) -> Cow<'a, str> {
+    let Some(mut found) = find(text, 0, secret) else {
+        return Cow::Borrowed(text);
+    };
+
+    // matches are never empty, so the search always advances
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    loop {
+        let (start, end) = found;
+        out.push_str(&text[copied..start]);
+        out.push_str(mask);
+        copied = end;
+        match find(text, end, secret) {
+            Some(next) => found = next,
+            None => break,
+        }
+    }
+    out.push_str(&text[copied..]);
+    Cow::Owned(out)
+}
+
+/// Byte range of the next occurrence of `secret` at or after `from`.
+fn find(text: &str, from: usize, secret: Secret) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let digits = |start: usize| {
+        bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+
+    match secret {
+        Secret::Literal("") | Secret::After("") => None,
+        Secret::Literal(literal) => text[from..]
+            .find(literal)
+            .map(|i| (from + i, from + i + literal.len())),
+        Secret::After(key) => {
+            let mut i = from;
+            loop {
+                let start = i + text.get(i..)?.find(key)? + key.len();
+                let len = text[start..]
+                    .find(char::is_whitespace)
+                    .unwrap_or(text.len() - start);
+                if len > 0 {
+                    return Some((start, start + len));
+                }
+                // skip the whitespace so the search always advances
+                i = start + text[start..].chars().next()?.len_utf8();
+            }
+        }
+        Secret::Digits(min) => {
+            let mut i = from;
+            while i < bytes.len() {
+                let len = digits(i);
+                if len == 0 {
+                    i += 1;
+                } else if len >= min.max(1) && (i == 0 || !bytes[i - 1].is_ascii_digit()) {
+                    return Some((i, i + len));
+                } else {
+                    i += len;
+                }
+            }
+            None
+        }
+        Secret::Ipv4 => {
+            let mut i = from;
+            while i < bytes.len() {
+                let boundary = i == 0 || !(bytes[i - 1].is_ascii_digit() || bytes[i - 1] == b'.');
+                if boundary && bytes[i].is_ascii_digit() {
+                    let mut end = i;
+                    let mut octets = 0;
+                    while octets < 4 {
+                        let len = digits(end);
+                        if !(1..=3).contains(&len) {
+                            break;
+                        }
+                        end += len;
+                        octets += 1;
+                        if octets < 4 && bytes.get(end) == Some(&b'.') {
+                            end += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                    if octets == 4 && bytes.get(end).is_none_or(|b| !b.is_ascii_digit()) {
+                        return Some((i, end));
+                    }
+                }
+                i += 1;
+            }
+            None
+        }
+    }
+}
+
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals() {
+        let secret = Secret::Literal("bob");
+        assert_eq!(redact("bob and bob", secret, "***"), "*** and ***");
+        assert!(matches!(redact("alice", secret, "***"), Cow::Borrowed(_)));
+        assert!(matches!(
+            redact("alice", Secret::Literal(""), "***"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn addresses() {
+        assert_eq!(
+            redact("from 192.168.0.1:80", Secret::Ipv4, "***"),
+            "from ***:80"
+        );
+        assert!(matches!(
+            redact("version 1.2.3, 1234.1.1.1", Secret::Ipv4, "***"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn after_keys() {
+        let secret = Secret::After("token=");
+        assert_eq!(redact("token=abc def", secret, "***"), "token=*** def");
+        assert_eq!(
+            redact("token=a token=\u{e9}", secret, "***"),
+            "token=*** token=***"
+        );
+        assert!(matches!(
+            redact("token= x", secret, "***"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            redact("token=\u{3000}\u{3000}", secret, "***"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            redact("a b  c", Secret::After(""), "***"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn digits() {
+        assert_eq!(
+            redact("serial 123456789012 id 1234", Secret::Digits(9), "***"),
+            "serial *** id 1234"
+        );
+    }
+
+    #[test]
+    fn chain() {
+        let redact = Redact::new(&DEFAULT_SECRETS).mask("#");
+        let text = redact.filter(Cow::Borrowed("ip 10.0.0.1 key=secret"), &Theme::default());
+        assert_eq!(text, "ip # key=#");
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn live_update() {
+        use crate::mock::{Call, MockBackend, Simulation};
+
+        static REDACT: Redact = Redact::new(&DEFAULT_SECRETS);
+        static FILTERS: [&dyn TextFilter; 1] = [&REDACT];
+
+        let _sim = Simulation::start();
+        let notification = crate::dynamic("").filters(&FILTERS).show().unwrap();
+        notification.text_cstr(c"token=abc").unwrap();
+        notification.text_cstr(c"done").unwrap();
+
+        let texts: Vec<_> = MockBackend::calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::UpdateText { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, ["token=***", "done"]);
+    }
+}
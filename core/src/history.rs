@@ -0,0 +1,136 @@
+use crate::{Level, manager};
+use alloc::string::String;
+use core::{fmt, time::Duration};
+
+/// What the [`NotificationManager`](crate::NotificationManager) did with a recorded
+/// notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Shown on the overlay.
+    Shown,
+    /// Passed to the [`Sink`](crate::policy::Sink) by a [`Rule`](crate::policy::Rule).
+    Rerouted,
+}
+
+/// Notification recorded in the history, see
+/// [`keep_history`](crate::NotificationManager::keep_history).
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// Time since boot when it was shown or rerouted.
+    pub time: Duration,
+    pub level: Level,
+    pub tag: Option<&'static str>,
+    pub text: String,
+    pub outcome: Outcome,
+}
+
+impl Entry {
+    /// Writes the entry as one JSON object, e.g.
+    /// `{"time_ms":1500,"level":"info","tag":"net","text":"Connected","outcome":"shown"}`.
+    pub fn write_json(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        let level = match self.level {
+            Level::Info => "info",
+            Level::Warn => "warn",
+            Level::Error => "error",
+        };
+        let outcome = match self.outcome {
+            Outcome::Shown => "shown",
+            Outcome::Rerouted => "rerouted",
+        };
+
+        write!(
+            out,
+            "{{\"time_ms\":{},\"level\":\"{level}\",\"tag\":",
+            self.time.as_millis()
+        )?;
+        match self.tag {
+            Some(tag) => string(out, tag)?,
+            None => out.write_str("null")?,
+        }
+        out.write_str(",\"text\":")?;
+        string(out, &self.text)?;
+        write!(out, ",\"outcome\":\"{outcome}\"}}")
+    }
+}
+
+/// Writes `text` as a JSON string.
+fn string(out: &mut impl fmt::Write, text: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in text.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if c < ' ' => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// Writes the history of the [`manager`](crate::manager) as JSON lines, oldest first.
+pub fn write(out: &mut impl fmt::Write) -> fmt::Result {
+    for entry in manager().history_entries() {
+        entry.write_json(out)?;
+        out.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Writes the history of the [`manager`](crate::manager) to a JSON lines file, e.g. on the SD
+/// card for a bug report. An existing file is replaced.
+#[cfg(feature = "std")]
+pub fn export(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    // formatted first, so the manager isn't locked during the file access
+    let mut text = String::new();
+    write(&mut text).map_err(std::io::Error::other)?;
+    std::fs::write(path, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let mut entry = Entry {
+            time: Duration::from_millis(1500),
+            level: Level::Warn,
+            tag: Some("net"),
+            text: String::from("say \"hi\"\\\n\u{1}ok"),
+            outcome: Outcome::Rerouted,
+        };
+        let mut line = String::new();
+        entry.write_json(&mut line).unwrap();
+        assert_eq!(
+            line,
+            r#"{"time_ms":1500,"level":"warn","tag":"net","text":"say \"hi\"\\\n\u0001ok","outcome":"rerouted"}"#
+        );
+
+        entry.tag = None;
+        entry.text = String::from("ünïcode");
+        line.clear();
+        entry.write_json(&mut line).unwrap();
+        assert!(line.contains(r#""tag":null,"text":"ünïcode""#));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn manager_history() {
+        use crate::{info, mock::Simulation};
+
+        let _sim = Simulation::start();
+        manager().keep_history(4);
+        manager().submit(info("a").tag("net"));
+        manager().update().unwrap();
+
+        let mut text = String::new();
+        write(&mut text).unwrap();
+        assert_eq!(
+            text,
+            "{\"time_ms\":0,\"level\":\"info\",\"tag\":\"net\",\"text\":\"a\",\"outcome\":\"shown\"}\n"
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{Notification, NotificationError, clock, dynamic};
+use core::time::Duration;
+
+/// Dynamic notification of a helper which is finished after a period without updates and
+/// recreated by the next update.
+pub(crate) struct IdleSlot {
+    notification: Option<Notification>,
+    timeout: Option<Duration>,
+    active: Duration,
+}
+
+impl IdleSlot {
+    pub(crate) fn new(text: &str) -> Result<Self, NotificationError> {
+        Ok(Self {
+            notification: Some(dynamic(text).show()?),
+            timeout: None,
+            active: clock::now(),
+        })
+    }
+
+    pub(crate) fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Replaces the text, showing a new notification if the previous one went idle.
+    pub(crate) fn text(&mut self, text: &str) -> Result<(), NotificationError> {
+        self.active = clock::now();
+        match &self.notification {
+            Some(notification) => notification.text(text),
+            None => {
+                self.notification = Some(dynamic(text).show()?);
+                Ok(())
+            }
+        }
+    }
+
+    /// Finishes the notification if it wasn't updated within the timeout.
+    pub(crate) fn poll(&mut self) {
+        let Some(timeout) = self.timeout else {
+            return;
+        };
+        if clock::now().saturating_sub(self.active) >= timeout {
+            self.notification = None;
+        }
+    }
+
+    pub(crate) fn get(&self) -> Option<&Notification> {
+        self.notification.as_ref()
+    }
+}
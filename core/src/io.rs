@@ -0,0 +1,39 @@
+use crate::{Active, Notification};
+use std::{io, string::String, vec::Vec};
+
+/// [`io::Write`] adapter which replaces the text of a dynamic notification on every flush.
+pub struct Writer<'a> {
+    notification: &'a Notification<Active>,
+    buffer: Vec<u8>,
+}
+
+impl<'a> Writer<'a> {
+    pub fn new(notification: &'a Notification<Active>) -> Self {
+        Self {
+            notification,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl io::Write for Writer<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let text = String::from_utf8_lossy(&self.buffer).into_owned();
+        self.buffer.clear();
+
+        self.notification.text(&text).map_err(io::Error::other)
+    }
+}
+
+impl Drop for Writer<'_> {
+    fn drop(&mut self) {
+        if !self.buffer.is_empty() {
+            let _ = io::Write::flush(self);
+        }
+    }
+}
@@ -1,10 +1,92 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
 
-use alloc::{ffi::CString, string::String};
-use core::marker::PhantomData;
-use notifications_sys as sys;
+pub mod batch;
+mod clock;
+pub mod countdown;
+pub mod degrade;
+pub mod details;
+pub mod escalator;
+pub mod filter;
+pub mod history;
+mod idle;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "log")]
+pub mod log;
+pub mod logging;
+mod macros;
+pub mod manager;
+pub mod mirror;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod notifier;
+pub mod panic;
+pub mod policy;
+pub mod prefs;
+pub mod progress;
+mod ring;
+#[cfg(feature = "screenshot")]
+pub mod screenshot;
+pub mod selftest;
+mod signal;
+#[cfg(feature = "slog")]
+pub mod slog;
+pub mod stats;
+pub mod strict;
+pub mod summary;
+pub mod template;
+pub mod text;
+pub mod theme;
+pub mod thread;
+pub mod trace;
+pub mod tracker;
+pub mod version;
+pub mod watchdog;
+pub mod wire;
+#[cfg(feature = "wups")]
+pub mod wups;
+
+pub use batch::BatchReport;
+pub use countdown::Countdown;
+pub use details::error_with_details;
+pub use escalator::Escalator;
+pub use filter::{Filters, TextFilter};
+pub use manager::{Level, NotificationManager, manager};
+pub use mirror::Mirror;
+pub use notifier::{Notifier, current};
+#[cfg(feature = "std")]
+pub use panic::install_panic_hook;
+pub use progress::{CancelToken, Progress, ProgressGroup};
+pub use ring::raw_enqueue;
+pub use selftest::self_test;
+pub use signal::WaitFinished;
+pub use template::Template;
+pub use text::text_list;
+pub use theme::{ReadingRate, Theme, set_theme, theme};
+pub use tracker::Tracker;
+pub use version::{Version, module_version, require_version};
+pub use watchdog::Watchdog;
+
+#[doc(hidden)]
+pub use alloc::format as __format;
+
+use alloc::{
+    borrow::Cow,
+    ffi::CString,
+    string::{String, ToString},
+};
+use core::{
+    any::Any,
+    ffi::CStr,
+    fmt::{Display, Write},
+    marker::PhantomData,
+    mem::ManuallyDrop,
+    panic::Location,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+};
+use notifications_sys::{self as sys, NotificationModuleStatus as S};
 use thiserror::Error;
 use wut::{
     gx2::color::Color,
@@ -12,99 +94,498 @@ use wut::{
 };
 
 static NOTIFY: Rrc = Rrc::new(
-    || unsafe {
-        sys::NotificationModule_InitLibrary();
+    || {
+        trace::call(Operation::InitLibrary, format_args!(""));
+        let status = unsafe { sys::NotificationModule_InitLibrary() };
+        trace::result(Operation::InitLibrary, status);
     },
-    || unsafe {
-        sys::NotificationModule_DeInitLibrary();
+    || {
+        trace::call(Operation::DeInitLibrary, format_args!(""));
+        let status = unsafe { sys::NotificationModule_DeInitLibrary() };
+        trace::result(Operation::DeInitLibrary, status);
     },
 );
 
+/// Copies a callback out of its lock, so a callback calling back into the crate doesn't
+/// deadlock.
+pub(crate) fn callback<T: Copy>(slot: &spin::Mutex<Option<T>>) -> Option<T> {
+    *slot.lock()
+}
+
 // region: NotificationError
 
-#[derive(Debug, Error)]
+/// Function of the notification module which reported an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum Operation {
+    /// The status was converted without naming the function.
+    Unknown,
+    InitLibrary,
+    DeInitLibrary,
+    GetVersion,
+    IsOverlayReady,
+    AddInfoNotificationEx,
+    AddErrorNotificationEx,
+    AddDynamicNotificationEx,
+    UpdateDynamicNotificationText,
+    UpdateDynamicNotificationTextColor,
+    UpdateDynamicNotificationBackgroundColor,
+    FinishDynamicNotification,
+    FinishDynamicNotificationWithShake,
+}
+
+/// Error of the notification module or of this wrapper.
+///
+/// Implements [`core::error::Error`] without requiring `std`, [`InternalZeroByte`] reports the
+/// [`NulError`](alloc::ffi::NulError) as its [`source`](core::error::Error::source).
+///
+/// Errors of the module carry the [`Operation`] which failed. With [`located_errors`], errors
+/// of [`show`](NotificationBuilder::show) are wrapped in [`Located`] with the caller which
+/// created the builder, match on [`kind`](NotificationError::kind) then.
+///
+/// [`InternalZeroByte`]: NotificationError::InternalZeroByte
+/// [`Located`]: NotificationError::Located
+#[derive(Debug, Clone, Error)]
 #[repr(i32)]
+#[non_exhaustive]
 pub enum NotificationError {
-    #[error("")]
-    ModuleNotFound = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_MODULE_NOT_FOUND,
-    #[error("")]
-    ModuleMissingExport =
-        sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_MODULE_MISSING_EXPORT,
-    #[error("")]
-    UnsupportedVersion =
-        sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_VERSION,
-    #[error("")]
-    InvalidArgument = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT,
-    #[error("")]
-    LibUninitialized = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_LIB_UNINITIALIZED,
-    #[error("")]
-    UnsupportedCommand =
-        sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_COMMAND,
-    #[error("")]
-    OverlayNotReady = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY,
-    #[error("")]
-    UnsupportedType = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE,
-    #[error("")]
-    AllocationFailed = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED,
-    #[error("")]
-    InvalidHandle = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_INVALID_HANDLE,
-    #[error("")]
-    Unknown(i32) = sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_UNKNOWN_ERROR,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_MODULE_NOT_FOUND)
+    )]
+    ModuleNotFound(Operation) = S::NOTIFICATION_MODULE_RESULT_MODULE_NOT_FOUND,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_MODULE_MISSING_EXPORT)
+    )]
+    ModuleMissingExport(Operation) = S::NOTIFICATION_MODULE_RESULT_MODULE_MISSING_EXPORT,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_VERSION)
+    )]
+    UnsupportedVersion(Operation) = S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_VERSION,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT)
+    )]
+    InvalidArgument(Operation) = S::NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_LIB_UNINITIALIZED)
+    )]
+    LibUninitialized(Operation) = S::NOTIFICATION_MODULE_RESULT_LIB_UNINITIALIZED,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_COMMAND)
+    )]
+    UnsupportedCommand(Operation) = S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_COMMAND,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY)
+    )]
+    OverlayNotReady(Operation) = S::NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE)
+    )]
+    UnsupportedType(Operation) = S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED)
+    )]
+    AllocationFailed(Operation) = S::NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED,
+    #[error(
+        "{} in {0:?}",
+        sys::status_str(S::NOTIFICATION_MODULE_RESULT_INVALID_HANDLE)
+    )]
+    InvalidHandle(Operation) = S::NOTIFICATION_MODULE_RESULT_INVALID_HANDLE,
+    #[error("Unknown status {0} in {1:?}")]
+    Unknown(i32, Operation) = S::NOTIFICATION_MODULE_RESULT_UNKNOWN_ERROR,
 
     #[error("Internal 0-byte")]
     InternalZeroByte(#[from] alloc::ffi::NulError),
+    #[error("Timed out")]
+    Timeout,
+    #[error("Queue full")]
+    QueueFull,
+    #[error("Notifications disabled")]
+    Disabled,
+    #[error("Dynamic notifications can't be shown from a finish callback")]
+    InCallback,
+    #[error("{0} ({1})")]
+    Located(Box<NotificationError>, &'static Location<'static>),
 }
 
 impl TryFrom<i32> for NotificationError {
     type Error = Self;
     fn try_from(value: i32) -> Result<Self, Self::Error> {
-        use sys::NotificationModuleStatus as S;
+        let op = Operation::Unknown;
         match value {
-            S::NOTIFICATION_MODULE_RESULT_SUCCESS => Ok(Self::Unknown(value)),
-            S::NOTIFICATION_MODULE_RESULT_MODULE_NOT_FOUND => Err(Self::ModuleNotFound),
-            S::NOTIFICATION_MODULE_RESULT_MODULE_MISSING_EXPORT => Err(Self::ModuleMissingExport),
-            S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_VERSION => Err(Self::UnsupportedVersion),
-            S::NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT => Err(Self::InvalidArgument),
-            S::NOTIFICATION_MODULE_RESULT_LIB_UNINITIALIZED => Err(Self::LibUninitialized),
-            S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_COMMAND => Err(Self::UnsupportedCommand),
-            S::NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY => Err(Self::OverlayNotReady),
-            S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE => Err(Self::UnsupportedType),
-            S::NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED => Err(Self::AllocationFailed),
-            S::NOTIFICATION_MODULE_RESULT_INVALID_HANDLE => Err(Self::InvalidHandle),
-            v => Err(Self::Unknown(v)),
+            S::NOTIFICATION_MODULE_RESULT_SUCCESS => Ok(Self::Unknown(value, op)),
+            S::NOTIFICATION_MODULE_RESULT_MODULE_NOT_FOUND => Err(Self::ModuleNotFound(op)),
+            S::NOTIFICATION_MODULE_RESULT_MODULE_MISSING_EXPORT => {
+                Err(Self::ModuleMissingExport(op))
+            }
+            S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_VERSION => Err(Self::UnsupportedVersion(op)),
+            S::NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT => Err(Self::InvalidArgument(op)),
+            S::NOTIFICATION_MODULE_RESULT_LIB_UNINITIALIZED => Err(Self::LibUninitialized(op)),
+            S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_COMMAND => Err(Self::UnsupportedCommand(op)),
+            S::NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY => Err(Self::OverlayNotReady(op)),
+            S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE => Err(Self::UnsupportedType(op)),
+            S::NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED => Err(Self::AllocationFailed(op)),
+            S::NOTIFICATION_MODULE_RESULT_INVALID_HANDLE => Err(Self::InvalidHandle(op)),
+            v => Err(Self::Unknown(v, op)),
+        }
+    }
+}
+
+impl NotificationError {
+    /// Converts the status of `operation`, like [`TryFrom<i32>`] but recording the
+    /// [`Operation`].
+    pub fn check(status: i32, operation: Operation) -> Result<(), Self> {
+        trace::result(operation, status);
+        match Self::try_from(status) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.with_operation(operation)),
+        }
+    }
+
+    /// Function of the notification module which reported the error, `None` for errors of this
+    /// wrapper.
+    pub fn operation(&self) -> Option<Operation> {
+        match self {
+            Self::ModuleNotFound(op)
+            | Self::ModuleMissingExport(op)
+            | Self::UnsupportedVersion(op)
+            | Self::InvalidArgument(op)
+            | Self::LibUninitialized(op)
+            | Self::UnsupportedCommand(op)
+            | Self::OverlayNotReady(op)
+            | Self::UnsupportedType(op)
+            | Self::AllocationFailed(op)
+            | Self::InvalidHandle(op)
+            | Self::Unknown(_, op) => Some(*op),
+            Self::Located(e, _) => e.operation(),
+            Self::InternalZeroByte(_)
+            | Self::Timeout
+            | Self::QueueFull
+            | Self::Disabled
+            | Self::InCallback => None,
+        }
+    }
+
+    /// Source location which issued the failed notification.
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        match self {
+            Self::Located(_, location) => Some(location),
+            _ => None,
+        }
+    }
+
+    /// The error without the [`location`](Self::location).
+    pub fn kind(&self) -> &Self {
+        match self {
+            Self::Located(e, _) => e.kind(),
+            e => e,
+        }
+    }
+
+    /// Wraps the error in [`Located`](Self::Located) if [`located_errors`] is enabled.
+    fn at(self, location: &'static Location<'static>) -> Self {
+        match self {
+            e @ Self::Located(..) => e,
+            e if LOCATED_ERRORS.load(Ordering::Relaxed) => Self::Located(Box::new(e), location),
+            e => e,
+        }
+    }
+
+    fn with_operation(mut self, operation: Operation) -> Self {
+        match &mut self {
+            Self::ModuleNotFound(op)
+            | Self::ModuleMissingExport(op)
+            | Self::UnsupportedVersion(op)
+            | Self::InvalidArgument(op)
+            | Self::LibUninitialized(op)
+            | Self::UnsupportedCommand(op)
+            | Self::OverlayNotReady(op)
+            | Self::UnsupportedType(op)
+            | Self::AllocationFailed(op)
+            | Self::InvalidHandle(op)
+            | Self::Unknown(_, op) => *op = operation,
+            Self::Located(e, _) => **e = e.clone().with_operation(operation),
+            Self::InternalZeroByte(_)
+            | Self::Timeout
+            | Self::QueueFull
+            | Self::Disabled
+            | Self::InCallback => {}
+        }
+        self
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for NotificationError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Self::ModuleNotFound(op) => defmt::write!(f, "ModuleNotFound({})", op),
+            Self::ModuleMissingExport(op) => defmt::write!(f, "ModuleMissingExport({})", op),
+            Self::UnsupportedVersion(op) => defmt::write!(f, "UnsupportedVersion({})", op),
+            Self::InvalidArgument(op) => defmt::write!(f, "InvalidArgument({})", op),
+            Self::LibUninitialized(op) => defmt::write!(f, "LibUninitialized({})", op),
+            Self::UnsupportedCommand(op) => defmt::write!(f, "UnsupportedCommand({})", op),
+            Self::OverlayNotReady(op) => defmt::write!(f, "OverlayNotReady({})", op),
+            Self::UnsupportedType(op) => defmt::write!(f, "UnsupportedType({})", op),
+            Self::AllocationFailed(op) => defmt::write!(f, "AllocationFailed({})", op),
+            Self::InvalidHandle(op) => defmt::write!(f, "InvalidHandle({})", op),
+            Self::Unknown(status, op) => defmt::write!(f, "Unknown({=i32}, {})", status, op),
+            Self::InternalZeroByte(e) => {
+                defmt::write!(f, "InternalZeroByte({=usize})", e.nul_position())
+            }
+            Self::Timeout => defmt::write!(f, "Timeout"),
+            Self::QueueFull => defmt::write!(f, "QueueFull"),
+            Self::Disabled => defmt::write!(f, "Disabled"),
+            Self::InCallback => defmt::write!(f, "InCallback"),
+            Self::Located(e, location) => defmt::write!(
+                f,
+                "{} ({=str}:{=u32})",
+                e.kind(),
+                location.file(),
+                location.line()
+            ),
         }
     }
 }
 
 // endregion
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use core::time::Duration;
+use signal::Signal;
+use spin::Mutex;
 
 // region: Notification
 
-pub struct Notification {
+/// Sequences and handles of all dynamic notifications which have not been finished yet.
+///
+/// Keyed by the sequence since the module reuses handles once a notification is gone, a stale
+/// [`Notification`] must not finish the one now owning its handle.
+static LIVE: Mutex<Vec<(u32, sys::NotificationModuleHandle)>> = Mutex::new(Vec::new());
+
+/// Removes the notification from [`LIVE`], returns `false` if it was already finished.
+fn untrack(sequence: u32) -> bool {
+    let mut live = LIVE.lock();
+    match live.iter().position(|(s, _)| *s == sequence) {
+        Some(index) => {
+            live.swap_remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+/// Next number of the crate wide, monotonically increasing notification sequence.
+fn next_sequence() -> u32 {
+    SEQUENCE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Restarts the sequence and forgets the live notifications, e.g. for a new simulation.
+#[cfg(feature = "mock")]
+pub(crate) fn reset() {
+    SEQUENCE.store(0, Ordering::Relaxed);
+    LIVE.lock().clear();
+}
+
+/// State of a [`Notification`] which is shown and can be updated.
+pub struct Active;
+
+/// Data attached to a notification with [`user_data`](NotificationBuilder::user_data).
+pub type UserData = Arc<dyn Any + Send + Sync>;
+
+/// State of a [`Notification`] which has been finished and is fading out.
+pub struct Finished;
+
+pub struct Notification<S = Active> {
     handle: sys::NotificationModuleHandle,
+    sequence: u32,
     delay: f32,
     shake: f32,
+    shown: Duration,
+    /// Prefix of the builder, e.g. the theme one, also applied to text updates.
+    prefix: &'static str,
+    /// Not finished on drop, see [`sticky`].
+    sticky: bool,
+    /// Last text, only tracked if the elapsed time is appended on finish.
+    text: Option<Mutex<String>>,
+    finished: Arc<Signal>,
+    user_data: Option<UserData>,
+    /// Chain applied to text updates, the global one if `None`.
+    filters: Option<Filters>,
+    /// Reused by the [`fmt::Write`](core::fmt::Write) impl.
+    buffer: String,
     _resource: RrcGuard,
+    _state: PhantomData<S>,
 }
 
-impl Notification {
+impl<S> Notification<S> {
+    /// Sequence number assigned when the notification was shown.
+    #[inline]
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    /// Data attached with [`user_data`](NotificationBuilder::user_data), if it is a `T`.
+    pub fn user_data<T: Any>(&self) -> Option<&T> {
+        self.user_data.as_deref()?.downcast_ref()
+    }
+
+    /// Completes once the overlay removed the notification.
+    pub fn finished(&self) -> WaitFinished {
+        WaitFinished(self.finished.clone())
+    }
+
+    /// The overlay removed the notification.
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_set()
+    }
+
+    /// Blocks until the overlay removed the notification, [`Timeout`] after `timeout`.
+    ///
+    /// [`Timeout`]: NotificationError::Timeout
+    pub fn wait_finished(&self, timeout: Duration) -> Result<(), NotificationError> {
+        let deadline = clock::real() + timeout;
+        while !self.is_finished() {
+            if clock::real() >= deadline {
+                return Err(NotificationError::Timeout);
+            }
+            unsafe { wut::bindings::OSYieldThread() };
+        }
+
+        Ok(())
+    }
+
+    fn into_state<N>(self) -> Notification<N> {
+        let this = ManuallyDrop::new(self);
+        Notification {
+            handle: this.handle,
+            sequence: this.sequence,
+            delay: this.delay,
+            shake: this.shake,
+            shown: this.shown,
+            prefix: this.prefix,
+            sticky: this.sticky,
+            text: unsafe { core::ptr::read(&this.text) },
+            finished: unsafe { core::ptr::read(&this.finished) },
+            user_data: unsafe { core::ptr::read(&this.user_data) },
+            filters: this.filters,
+            buffer: unsafe { core::ptr::read(&this.buffer) },
+            _resource: unsafe { core::ptr::read(&this._resource) },
+            _state: PhantomData,
+        }
+    }
+
+    /// Appends the elapsed time if requested and fades out the notification.
+    fn finish_status(&self) -> i32 {
+        if let Some(text) = &self.text {
+            let elapsed = clock::now().saturating_sub(self.shown).as_secs_f32();
+            let mut text = text.lock().clone();
+            let _ = write!(text, " ({elapsed:.1} s)");
+
+            if let Ok(text) = CString::new(text) {
+                trace::call(
+                    Operation::UpdateDynamicNotificationText,
+                    format_args!("{}, {text:?}", self.handle),
+                );
+                let status = unsafe {
+                    sys::NotificationModule_UpdateDynamicNotificationText(
+                        self.handle,
+                        text.as_ptr(),
+                    )
+                };
+                strict::ignore(NotificationError::check(
+                    status,
+                    Operation::UpdateDynamicNotificationText,
+                ));
+            }
+        }
+
+        trace::call(
+            Operation::FinishDynamicNotificationWithShake,
+            format_args!("{}, {}, {}", self.handle, self.delay, self.shake),
+        );
+        unsafe {
+            sys::NotificationModule_FinishDynamicNotificationWithShake(
+                self.handle,
+                self.delay,
+                self.shake,
+            )
+        }
+    }
+}
+
+impl Notification<Active> {
+    /// Replaces the text without copying it unless a prefix has to be applied or the
+    /// [`filters`](filter::filters) change it.
+    pub fn text_cstr(&self, text: &CStr) -> Result<(), NotificationError> {
+        let unchanged = self.prefix.is_empty()
+            && text.to_str().is_ok_and(|text| {
+                matches!(
+                    filter::apply(Cow::Borrowed(text), self.filters),
+                    Cow::Borrowed(_)
+                )
+            });
+        if !unchanged {
+            return self.text(&text.to_string_lossy());
+        }
+        if let Some(last) = &self.text {
+            *last.lock() = text.to_string_lossy().into_owned();
+        }
+
+        trace::call(
+            Operation::UpdateDynamicNotificationText,
+            format_args!("{}, {text:?}", self.handle),
+        );
+        let status = unsafe {
+            sys::NotificationModule_UpdateDynamicNotificationText(self.handle, text.as_ptr())
+        };
+        NotificationError::check(status, Operation::UpdateDynamicNotificationText)
+    }
+
     #[inline]
     pub fn text(&self, text: &str) -> Result<(), NotificationError> {
-        let text = CString::new(text)?;
+        let text = if self.prefix.is_empty() {
+            Cow::Borrowed(text)
+        } else {
+            Cow::Owned(alloc::format!("{}{text}", self.prefix))
+        };
+        degrade::overflow(Level::Info, &text);
+        let text = filter::apply(text, self.filters);
+        if let Some(last) = &self.text {
+            *last.lock() = String::from(text.as_ref());
+        }
+
+        let text = CString::new(text.into_owned())?;
 
+        trace::call(
+            Operation::UpdateDynamicNotificationText,
+            format_args!("{}, {text:?}", self.handle),
+        );
         let status = unsafe {
             sys::NotificationModule_UpdateDynamicNotificationText(self.handle, text.as_ptr())
         };
-        NotificationError::try_from(status)?;
+        NotificationError::check(status, Operation::UpdateDynamicNotificationText)?;
 
         Ok(())
     }
 
     #[inline]
     pub fn text_color(&self, color: Color) -> Result<(), NotificationError> {
+        trace::call(
+            Operation::UpdateDynamicNotificationTextColor,
+            format_args!("{}, {color:?}", self.handle),
+        );
         let status = unsafe {
             sys::NotificationModule_UpdateDynamicNotificationTextColor(
                 self.handle,
@@ -116,13 +597,17 @@ impl Notification {
                 },
             )
         };
-        NotificationError::try_from(status)?;
+        NotificationError::check(status, Operation::UpdateDynamicNotificationTextColor)?;
 
         Ok(())
     }
 
     #[inline]
     pub fn bg_color(&self, color: Color) -> Result<(), NotificationError> {
+        trace::call(
+            Operation::UpdateDynamicNotificationBackgroundColor,
+            format_args!("{}, {color:?}", self.handle),
+        );
         let status = unsafe {
             sys::NotificationModule_UpdateDynamicNotificationBackgroundColor(
                 self.handle,
@@ -134,27 +619,98 @@ impl Notification {
                 },
             )
         };
-        NotificationError::try_from(status)?;
+        NotificationError::check(status, Operation::UpdateDynamicNotificationBackgroundColor)?;
 
         Ok(())
     }
+
+    /// Restores the text color of the active [`Theme`].
+    #[inline]
+    pub fn reset_text_color(&self) -> Result<(), NotificationError> {
+        self.text_color(default_text_color())
+    }
+
+    /// Restores the background color of the active [`Theme`].
+    #[inline]
+    pub fn reset_bg_color(&self) -> Result<(), NotificationError> {
+        self.bg_color(default_background_color())
+    }
+
+    /// Fades out the notification immediately, ignoring the configured delay and shake.
+    pub fn dismiss(mut self) {
+        self.delay = 0.0;
+        self.shake = 0.0;
+        if self.sticky {
+            strict::ignore(self.finish().map(drop));
+        }
+    }
+
+    /// Fades out the notification after the configured delay and shake.
+    pub fn finish(self) -> Result<Notification<Finished>, NotificationError> {
+        let finished = self.into_state::<Finished>();
+        if !untrack(finished.sequence) {
+            return Ok(finished);
+        }
+
+        let status = finished.finish_status();
+        NotificationError::check(status, Operation::FinishDynamicNotificationWithShake)?;
+
+        Ok(finished)
+    }
+
+    /// Fades out the notification after `delay` and `shake` instead of the configured ones.
+    ///
+    /// Both are clamped to [`Theme::max_duration`] like the ones of the builder.
+    pub fn finish_with(
+        mut self,
+        delay: Duration,
+        shake: Option<Duration>,
+    ) -> Result<Notification<Finished>, NotificationError> {
+        let max = theme().max_duration.unwrap_or(Duration::MAX);
+        self.delay = delay.min(max).as_secs_f32();
+        self.shake = shake.map_or(0.0, |d| d.min(max).as_secs_f32());
+        self.finish()
+    }
 }
 
-impl Drop for Notification {
+/// Fades out the notification, errors are reported to [`strict`] since the overlay may already
+/// have removed it. Use [`finish`](Notification::finish) to handle them.
+///
+/// [`sticky`] notifications stay visible.
+impl<S> Drop for Notification<S> {
     fn drop(&mut self) {
-        let status = unsafe {
-            sys::NotificationModule_FinishDynamicNotificationWithShake(
-                self.handle,
-                self.delay,
-                self.shake,
-            )
-        };
-        NotificationError::try_from(status).unwrap();
+        if self.sticky || !untrack(self.sequence) {
+            return;
+        }
+
+        let status = self.finish_status();
+        strict::ignore(NotificationError::check(
+            status,
+            Operation::FinishDynamicNotificationWithShake,
+        ));
     }
 }
 
-unsafe impl Sync for Notification {}
-unsafe impl Send for Notification {}
+/// Replaces the text, `write!(notification, "Frame {n}: {ms} ms")` formats into a buffer reused
+/// between calls and updates the text once.
+impl Write for Notification<Active> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.text(s).map_err(|_| core::fmt::Error)
+    }
+
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> core::fmt::Result {
+        let mut buffer = core::mem::take(&mut self.buffer);
+        buffer.clear();
+        let result = buffer
+            .write_fmt(args)
+            .and_then(|()| self.write_str(&buffer));
+        self.buffer = buffer;
+        result
+    }
+}
+
+unsafe impl<S> Sync for Notification<S> {}
+unsafe impl<S> Send for Notification<S> {}
 
 // endregion
 
@@ -165,26 +721,55 @@ pub struct Info;
 pub struct Error;
 
 pub trait NotificationType: Sized {
+    /// Result of showing the notification, the handle for dynamic notifications and the
+    /// sequence number otherwise.
     type T;
-    fn show(builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError>;
+    /// Level the text is passed to the [`Sink`](policy::Sink) with when shortened in degraded
+    /// mode.
+    const LEVEL: Level;
+    /// Shows the notification, the builder keeps its callback if the module rejected it, so
+    /// the [`NotificationManager`] can retry.
+    fn show(builder: &mut NotificationBuilder<Self>) -> Result<Self::T, NotificationError>;
+    /// Called instead of [`show`](Self::show) from a finish callback.
+    fn defer(builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError>;
 }
 
 impl NotificationType for Dynamic {
     type T = Notification;
+    const LEVEL: Level = Level::Info;
 
-    fn show(builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
-        let text = CString::new(builder.text)?;
-        let callback: sys::NotificationModuleNotificationFinishedCallback = match builder.callback {
-            Some(_) => Some(notification_callback),
-            None => None,
-        };
-        let context = match builder.callback {
-            Some(f) => Box::into_raw(f) as *mut core::ffi::c_void,
-            None => core::ptr::null_mut(),
+    fn defer(_builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
+        Err(NotificationError::InCallback)
+    }
+
+    fn show(builder: &mut NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
+        let text = builder.c_text()?;
+
+        // always registered, so the finish can be awaited
+        let finished = Arc::new(Signal::default());
+        let callback: sys::NotificationModuleNotificationFinishedCallback =
+            Some(notification_callback);
+        let context = {
+            let finished = finished.clone();
+            let user = builder.callback.take();
+            let f: Box<dyn FnOnce()> = Box::new(move || {
+                if let Some(f) = user {
+                    f();
+                }
+                finished.set();
+            });
+            Box::into_raw(Box::new(f)) as *mut core::ffi::c_void
         };
 
         let r = NOTIFY.acquire();
         let mut handle = sys::NotificationModuleHandle::default();
+        trace::call(
+            Operation::AddDynamicNotificationEx,
+            format_args!(
+                "{text:?}, {:?}, {:?}, {}",
+                builder.text_color, builder.background_color, builder.keep_until_shown
+            ),
+        );
         let status = unsafe {
             sys::NotificationModule_AddDynamicNotificationEx(
                 text.as_ptr(),
@@ -206,32 +791,73 @@ impl NotificationType for Dynamic {
                 builder.keep_until_shown,
             )
         };
-        NotificationError::try_from(status)?;
+        degrade::observe(status);
+        if let Err(e) = NotificationError::check(status, Operation::AddDynamicNotificationEx) {
+            // the module didn't take the callback, calling it again only sets the unused signal
+            builder.callback = Some(unsafe { Box::from_raw(context.cast()) });
+            return Err(e);
+        }
+        stats::shown();
+        let sequence = next_sequence();
+        LIVE.lock().push((sequence, handle));
 
         Ok(Notification {
             handle,
+            sequence,
             delay: builder.delay.map_or(0.0, |d| d.as_secs_f32()),
             shake: builder.shake.map_or(0.0, |d| d.as_secs_f32()),
+            shown: clock::now(),
+            prefix: builder.prefix,
+            sticky: builder.sticky,
+            text: builder
+                .append_elapsed
+                .then(|| Mutex::new(String::from_utf8_lossy(text.to_bytes()).into_owned())),
+            finished,
+            user_data: builder.user_data.take(),
+            filters: builder.filters,
+            buffer: String::new(),
             _resource: r,
+            _state: PhantomData,
         })
     }
 }
 
 impl NotificationType for Info {
-    type T = ();
+    type T = u32;
+    const LEVEL: Level = Level::Info;
 
-    fn show(builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
-        let text = CString::new(builder.text)?;
+    fn defer(mut builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
+        let sequence = *builder.sequence.get_or_insert_with(next_sequence);
+        manager::defer(builder.into());
+        Ok(sequence)
+    }
+
+    fn show(builder: &mut NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
+        let text = builder.c_text()?;
+        // not registered with the module in degraded mode, run once shown instead
+        let degraded = degrade::is_degraded()
+            .then(|| builder.callback.take())
+            .flatten();
         let callback: sys::NotificationModuleNotificationFinishedCallback = match builder.callback {
             Some(_) => Some(notification_callback),
             None => None,
         };
-        let context = match builder.callback {
+        let context = match builder.callback.take() {
             Some(f) => Box::into_raw(f) as *mut core::ffi::c_void,
             None => core::ptr::null_mut(),
         };
 
         let _r = NOTIFY.acquire();
+        trace::call(
+            Operation::AddInfoNotificationEx,
+            format_args!(
+                "{text:?}, {:?}, {:?}, {:?}, {}",
+                builder.duration,
+                builder.text_color,
+                builder.background_color,
+                builder.keep_until_shown
+            ),
+        );
         let status = unsafe {
             sys::NotificationModule_AddInfoNotificationEx(
                 text.as_ptr(),
@@ -253,27 +879,66 @@ impl NotificationType for Info {
                 builder.keep_until_shown,
             )
         };
-        NotificationError::try_from(status)?;
+        degrade::observe(status);
+        if let Err(e) = NotificationError::check(status, Operation::AddInfoNotificationEx) {
+            // the module didn't take the callback
+            builder.callback = if context.is_null() {
+                degraded
+            } else {
+                Some(unsafe { Box::from_raw(context.cast()) })
+            };
+            return Err(e);
+        }
+        stats::shown();
 
-        Ok(())
+        let sequence = builder.sequence.unwrap_or_else(next_sequence);
+        if let Some(callback) = degraded {
+            run_callback(callback);
+        }
+        Ok(sequence)
     }
 }
 
 impl NotificationType for Error {
-    type T = ();
+    type T = u32;
+    const LEVEL: Level = Level::Error;
 
-    fn show(builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
-        let text = CString::new(builder.text)?;
+    fn defer(mut builder: NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
+        let sequence = *builder.sequence.get_or_insert_with(next_sequence);
+        manager::defer(builder.into());
+        Ok(sequence)
+    }
+
+    fn show(builder: &mut NotificationBuilder<Self>) -> Result<Self::T, NotificationError> {
+        #[cfg(feature = "screenshot")]
+        screenshot::attach(builder);
+
+        let text = builder.c_text()?;
+        // not registered with the module in degraded mode, run once shown instead
+        let degraded = degrade::is_degraded()
+            .then(|| builder.callback.take())
+            .flatten();
         let callback: sys::NotificationModuleNotificationFinishedCallback = match builder.callback {
             Some(_) => Some(notification_callback),
             None => None,
         };
-        let context = match builder.callback {
+        let context = match builder.callback.take() {
             Some(f) => Box::into_raw(f) as *mut core::ffi::c_void,
             None => core::ptr::null_mut(),
         };
 
         let _r = NOTIFY.acquire();
+        trace::call(
+            Operation::AddErrorNotificationEx,
+            format_args!(
+                "{text:?}, {:?}, {:?}, {:?}, {:?}, {}",
+                builder.duration,
+                builder.shake,
+                builder.text_color,
+                builder.background_color,
+                builder.keep_until_shown
+            ),
+        );
         let status = unsafe {
             sys::NotificationModule_AddErrorNotificationEx(
                 text.as_ptr(),
@@ -296,44 +961,195 @@ impl NotificationType for Error {
                 builder.keep_until_shown,
             )
         };
-        NotificationError::try_from(status)?;
+        degrade::observe(status);
+        if let Err(e) = NotificationError::check(status, Operation::AddErrorNotificationEx) {
+            // the module didn't take the callback
+            builder.callback = if context.is_null() {
+                degraded
+            } else {
+                Some(unsafe { Box::from_raw(context.cast()) })
+            };
+            return Err(e);
+        }
+        stats::shown();
+        if !builder.summarized {
+            summary::record(Level::Error);
+        }
+        stats::error(builder.tag);
 
-        Ok(())
+        let sequence = builder.sequence.unwrap_or_else(next_sequence);
+        if let Some(callback) = degraded {
+            run_callback(callback);
+        }
+        Ok(sequence)
     }
 }
 
 pub struct NotificationBuilder<T: NotificationType> {
+    location: &'static Location<'static>,
+    tag: Option<&'static str>,
+    prefix: &'static str,
+    thread_name: bool,
+    timestamp: bool,
     text: String,
+    /// Text set with [`text_cstr`](Self::text_cstr), replaces `text`.
+    raw: Option<Cow<'static, CStr>>,
+    /// Text set with [`text_lazy`](Self::text_lazy), replaces `text` once resolved.
+    lazy: Option<Box<dyn FnOnce() -> String>>,
+    /// Complete text encoded ahead of time, e.g. for the history, taken when shown.
+    encoded: Option<CString>,
+    fields: Vec<(&'static str, String)>,
     duration: Duration,
     text_color: Color,
     background_color: Color,
     callback: Option<Box<Box<dyn FnOnce()>>>,
+    /// Highest level of the notifications chained with [`then`](Self::then).
+    chain: Option<Level>,
+    user_data: Option<UserData>,
+    data_callback: Option<Box<dyn FnOnce(Option<UserData>)>>,
     keep_until_shown: bool,
     shake: Option<Duration>,
     delay: Option<Duration>,
+    append_elapsed: bool,
+    append_location: bool,
+    auto_duration: bool,
+    filters: Option<Filters>,
+    /// Reserved when the notification was deferred, so it keeps the sequence returned then.
+    sequence: Option<u32>,
+    sticky: bool,
+    /// Already counted in the [`summary`] by its source, e.g. a logged record.
+    summarized: bool,
     _marker: PhantomData<T>,
 }
 
-impl<T: NotificationType> Default for NotificationBuilder<T> {
-    fn default() -> Self {
+impl<T: NotificationType> NotificationBuilder<T> {
+    /// Builders are only created through [`dynamic`], [`info`], [`sticky`] and [`error`], so a
+    /// notification can't be shown without text.
+    #[track_caller]
+    fn new(text: &str) -> Self {
+        let theme = theme();
         Self {
-            text: String::from(""),
-            duration: Duration::from_secs(5),
-            text_color: Color::white(),
-            background_color: Color::black().opacity(0.5).into(),
+            location: Location::caller(),
+            tag: None,
+            prefix: theme.prefix,
+            thread_name: theme.thread_name,
+            timestamp: theme.timestamp,
+            text: String::from(text),
+            raw: None,
+            lazy: None,
+            encoded: None,
+            fields: Vec::new(),
+            duration: theme.duration,
+            text_color: theme.text_color,
+            background_color: theme.background_color,
             callback: None,
+            chain: None,
+            user_data: None,
+            data_callback: None,
             keep_until_shown: true,
             shake: None,
             delay: None,
+            append_elapsed: false,
+            append_location: false,
+            auto_duration: false,
+            filters: None,
+            sequence: None,
+            sticky: false,
+            summarized: false,
             _marker: PhantomData,
         }
     }
 }
 
 impl<T: NotificationType> NotificationBuilder<T> {
+    /// Category matched against the [`Rule`](policy::Rule)s of the [`NotificationManager`].
+    pub fn tag(mut self, tag: &'static str) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Prepended to the text, defaults to the prefix of the active [`Theme`].
+    pub fn prefix(mut self, prefix: &'static str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Styles the notification like new builders are styled by the active theme, error
+    /// notifications with the error fields of `theme`.
+    pub fn apply_theme(mut self, theme: &Theme) -> Self {
+        self.thread_name = theme.thread_name;
+        self.timestamp = theme.timestamp;
+        if T::LEVEL == Level::Error {
+            self.prefix = theme.error_prefix;
+            self.duration = theme.error_duration;
+            self.text_color = theme.error_text_color;
+            self.background_color = theme.error_background_color;
+            self.shake = theme.error_shake;
+        } else {
+            self.prefix = theme.prefix;
+            self.duration = theme.duration;
+            self.text_color = theme.text_color;
+            self.background_color = theme.background_color;
+        }
+        self
+    }
+
+    /// Prefix the text with the name of the thread calling [`show`](Self::show), e.g.
+    /// `"[loader] "`.
+    pub fn thread_name(mut self, enable: bool) -> Self {
+        self.thread_name = enable;
+        self
+    }
+
+    /// Prefix the text with the time of [`show`](Self::show), e.g. `"[14:02:55] "`.
+    pub fn timestamp(mut self, enable: bool) -> Self {
+        self.timestamp = enable;
+        self
+    }
+
+    /// Structured key/value pair, appended to the text as `" key=value"`.
+    pub fn field(mut self, key: &'static str, value: impl Display) -> Self {
+        self.fields.push((key, value.to_string()));
+        self
+    }
+
+    /// Fields added with [`field`](Self::field) in insertion order.
+    pub fn fields(&self) -> &[(&'static str, String)] {
+        &self.fields
+    }
+
     /// Content of the notification.
     pub fn text(mut self, text: &str) -> Self {
         self.text = String::from(text);
+        self.raw = None;
+        self.lazy = None;
+        self
+    }
+
+    /// Content of the notification, only built once the notification is shown.
+    ///
+    /// Expensive formatting is skipped for notifications which are dropped by a
+    /// [`Rule`](policy::Rule), mute or max. age of the [`NotificationManager`].
+    pub fn text_lazy<F: 'static + FnOnce() -> String>(mut self, text: F) -> Self {
+        self.raw = None;
+        self.lazy = Some(Box::new(text));
+        self
+    }
+
+    /// Content of the notification as NUL-terminated string, e.g. a `c"…"` literal.
+    ///
+    /// Passed to the module without copying or validating it, unless a prefix, fields or other
+    /// additions have to be applied.
+    pub fn text_cstr(mut self, text: &'static CStr) -> Self {
+        self.lazy = None;
+        self.raw = Some(Cow::Borrowed(text));
+        self
+    }
+
+    /// Like [`text_cstr`](Self::text_cstr) for owned strings, e.g. received over FFI.
+    pub fn text_cstr_owned(mut self, text: CString) -> Self {
+        self.lazy = None;
+        self.raw = Some(Cow::Owned(text));
         self
     }
 
@@ -343,6 +1159,19 @@ impl<T: NotificationType> NotificationBuilder<T> {
         self
     }
 
+    /// Text preprocessing chain used instead of the global [`filters`](filter::filters).
+    pub fn filters(mut self, filters: Filters) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    /// Derive the duration from the length of the text and the [`ReadingRate`] of the active
+    /// [`Theme`], replacing [`duration`](Self::duration).
+    pub fn auto_duration(mut self) -> Self {
+        self.auto_duration = true;
+        self
+    }
+
     /// Text color of the Notification.
     pub fn text_color(mut self, color: Color) -> Self {
         self.text_color = color;
@@ -361,6 +1190,42 @@ impl<T: NotificationType> NotificationBuilder<T> {
         self
     }
 
+    /// Attaches app-level state, available through [`Notification::user_data`] and passed to
+    /// the [`on_finished`](Self::on_finished) callback.
+    pub fn user_data<D: Any + Send + Sync>(mut self, data: D) -> Self {
+        self.user_data = Some(Arc::new(data));
+        self
+    }
+
+    /// Like [`callback`](Self::callback), receiving the [`user_data`](Self::user_data).
+    ///
+    /// Called after the callback set with [`callback`](Self::callback).
+    pub fn on_finished<F: 'static + FnOnce(Option<UserData>)>(mut self, callback: F) -> Self {
+        self.data_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Shows `next` once this notification finished, after the [`callback`](Self::callback).
+    ///
+    /// With [`inherit_priority`](NotificationManager::inherit_priority) the whole chain is queued
+    /// with the highest level in it, so context shown before an error isn't delayed behind
+    /// other notifications.
+    ///
+    /// `next` is queued in the [`NotificationManager`] and shown on its next
+    /// [`update`](NotificationManager::update). Calls are chained, so `a.then(b.then(c))` shows
+    /// `a`, `b` and `c` in order. Setting a callback afterwards replaces the chain.
+    pub fn then(mut self, next: impl Into<manager::NotificationSpec>) -> Self {
+        let previous = self.callback.take();
+        let next = next.into();
+        self.chain = self.chain.max(Some(next.chain_level()));
+        self.callback(move || {
+            if let Some(f) = previous {
+                f();
+            }
+            manager::defer(next);
+        })
+    }
+
     /// The Notification will be stored in a queue until can be shown.
     pub fn keep_until_shown(mut self, keep: bool) -> Self {
         self.keep_until_shown = keep;
@@ -368,8 +1233,224 @@ impl<T: NotificationType> NotificationBuilder<T> {
     }
 
     /// Queues the notification for display.
-    pub fn show(self) -> Result<T::T, NotificationError> {
-        T::show(self)
+    ///
+    /// With [`located_errors`], errors are [`Located`](NotificationError::Located) at the
+    /// [`caller`](Self::caller).
+    ///
+    /// Info and error notifications may be shown from a finish callback, e.g. to chain messages.
+    /// They are handed to the [`NotificationManager`] and shown on its next
+    /// [`update`](NotificationManager::update) with the sequence returned here, dynamic
+    /// notifications fail with [`InCallback`](NotificationError::InCallback). Deferred
+    /// notifications are never shown if the manager isn't updated, e.g. once per frame.
+    ///
+    /// Duration, shake and delay are clamped to [`Theme::max_duration`].
+    pub fn show(mut self) -> Result<T::T, NotificationError> {
+        self.prepare();
+
+        let location = self.location;
+        if in_callback() {
+            return T::defer(self).map_err(|e| e.at(location));
+        }
+        T::show(&mut self).map_err(|e| e.at(location))
+    }
+
+    /// Like [`show`](Self::show), but keeps the builder to retry if showing failed.
+    pub(crate) fn try_show(&mut self) -> Result<T::T, NotificationError> {
+        self.prepare();
+        let location = self.location;
+        T::show(self).map_err(|e| e.at(location))
+    }
+
+    /// Merges the data callback and applies the theme, repeating it for a retry is a no-op.
+    fn prepare(&mut self) {
+        if let Some(f) = self.data_callback.take() {
+            let previous = self.callback.take();
+            let data = self.user_data.clone();
+            self.callback = Some(Box::new(Box::new(move || {
+                if let Some(previous) = previous {
+                    previous();
+                }
+                f(data);
+            })));
+        }
+
+        let theme = theme();
+        if self.auto_duration {
+            self.resolve();
+            self.duration = match &self.raw {
+                Some(raw) => theme.reading.duration(&raw.to_string_lossy()),
+                None => theme.reading.duration(&self.text),
+            };
+        }
+
+        if let Some(max) = theme.max_duration {
+            self.duration = self.duration.min(max);
+            self.shake = self.shake.map(|d| d.min(max));
+            self.delay = self.delay.map(|d| d.min(max));
+        }
+    }
+
+    /// Source location which created the builder, recorded in errors of [`show`](Self::show).
+    pub fn caller(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Prefixes and text as C string.
+    ///
+    /// Text set with [`text_cstr`](Self::text_cstr) is passed through without a copy if nothing
+    /// has to be added.
+    fn c_text(&mut self) -> Result<Cow<'static, CStr>, NotificationError> {
+        if let Some(text) = self.encoded.take() {
+            return Ok(Cow::Owned(text));
+        }
+        self.resolve();
+        let mut body = Cow::Borrowed(self.text.as_str());
+        if let Some(raw) = &self.raw {
+            let plain = self.prefix.is_empty()
+                && !self.timestamp
+                && !self.thread_name
+                && self.fields.is_empty()
+                && !(cfg!(debug_assertions) && self.append_location);
+            let unchanged = raw.to_str().is_ok_and(|text| {
+                matches!(
+                    filter::apply(Cow::Borrowed(text), self.filters),
+                    Cow::Borrowed(_)
+                )
+            });
+            if plain && unchanged {
+                return Ok(raw.clone());
+            }
+            body = raw.to_string_lossy();
+        }
+
+        let mut text = String::from(self.prefix);
+        if self.timestamp {
+            clock::timestamp(&mut text);
+        }
+        if self.thread_name {
+            if let Some(name) = thread::current_name() {
+                text.push('[');
+                text.push_str(&name);
+                text.push_str("] ");
+            }
+        }
+        text.push_str(&body);
+        for (key, value) in &self.fields {
+            let _ = write!(text, " {key}={value}");
+        }
+        if cfg!(debug_assertions) && self.append_location {
+            let _ = write!(text, " ({}:{})", self.location.file(), self.location.line());
+        }
+        degrade::overflow(T::LEVEL, &text);
+        let text = filter::apply(Cow::Owned(text), self.filters).into_owned();
+
+        Ok(Cow::Owned(CString::new(text)?))
+    }
+
+    /// Encodes the text if not done yet and returns a copy of it, e.g. for the history.
+    pub(crate) fn encoded_text(&mut self) -> Option<String> {
+        if self.encoded.is_none() {
+            self.encoded = self.c_text().ok().map(Cow::into_owned);
+        }
+        self.encoded
+            .as_ref()
+            .map(|text| text.to_string_lossy().into_owned())
+    }
+
+    /// Encodes the complete text ahead of showing it, e.g. for a batch queued at once.
+    ///
+    /// Lazy texts and timestamps are left to be built once shown.
+    pub(crate) fn encode(&mut self) {
+        if self.lazy.is_some() || self.timestamp || self.encoded.is_some() {
+            return;
+        }
+        self.encoded = self.c_text().ok().and_then(|text| match text {
+            Cow::Owned(text) => Some(text),
+            Cow::Borrowed(_) => None,
+        });
+    }
+
+    /// Builds the text set with [`text_lazy`](Self::text_lazy).
+    fn resolve(&mut self) {
+        if let Some(f) = self.lazy.take() {
+            self.text = f();
+        }
+    }
+
+    /// Estimated heap memory owned by the builder.
+    pub(crate) fn heap_size(&self) -> usize {
+        let raw = match &self.raw {
+            Some(Cow::Owned(raw)) => raw.as_bytes_with_nul().len(),
+            _ => 0,
+        };
+        let encoded = self
+            .encoded
+            .as_ref()
+            .map_or(0, |text| text.as_bytes_with_nul().len());
+        let fields = self
+            .fields
+            .iter()
+            .map(|(_, value)| core::mem::size_of::<(&str, String)>() + value.capacity())
+            .sum::<usize>();
+        self.text.capacity() + raw + encoded + fields
+    }
+
+    /// Copy of the builder without the callback, lazy text is resolved.
+    pub(crate) fn detached(&mut self) -> Self {
+        self.resolve();
+        Self {
+            location: self.location,
+            tag: self.tag,
+            prefix: self.prefix,
+            thread_name: self.thread_name,
+            timestamp: self.timestamp,
+            text: self.text.clone(),
+            raw: self.raw.clone(),
+            lazy: None,
+            encoded: None,
+            fields: self.fields.clone(),
+            duration: self.duration,
+            text_color: self.text_color,
+            background_color: self.background_color,
+            callback: None,
+            chain: self.chain,
+            user_data: self.user_data.clone(),
+            data_callback: None,
+            keep_until_shown: self.keep_until_shown,
+            shake: self.shake,
+            delay: self.delay,
+            append_elapsed: self.append_elapsed,
+            append_location: self.append_location,
+            auto_duration: self.auto_duration,
+            filters: self.filters,
+            sequence: self.sequence,
+            sticky: self.sticky,
+            summarized: self.summarized,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: NotificationType<T = u32>> NotificationBuilder<T> {
+    /// Shows the notification like [`show`](Self::show) and completes once it finished, instead
+    /// of passing a [`callback`](Self::callback).
+    ///
+    /// Completes once shown in degraded mode, where finish callbacks run right away.
+    pub async fn show_async(mut self) -> Result<u32, NotificationError> {
+        let finished = Arc::new(Signal::default());
+        let signal = finished.clone();
+        let previous = self.callback.take();
+        self = self.callback(move || {
+            if let Some(previous) = previous {
+                previous();
+            }
+            signal.set();
+        });
+
+        let sequence = self.show()?;
+        WaitFinished(finished).await;
+        Ok(sequence)
     }
 }
 
@@ -383,6 +1464,50 @@ impl NotificationBuilder<Dynamic> {
         self.delay = duration;
         self
     }
+
+    /// Append the time the notification was shown to its text when it is finished, e.g.
+    /// `"Installed (12.4 s)"`.
+    pub fn append_elapsed(mut self) -> Self {
+        self.append_elapsed = true;
+        self
+    }
+}
+
+impl NotificationBuilder<Info> {
+    /// Same notification as error notification, styled by the error fields of `theme`.
+    pub(crate) fn into_error(self, theme: &Theme) -> NotificationBuilder<Error> {
+        NotificationBuilder {
+            location: self.location,
+            tag: self.tag,
+            prefix: self.prefix,
+            thread_name: self.thread_name,
+            timestamp: self.timestamp,
+            text: self.text,
+            raw: self.raw,
+            lazy: self.lazy,
+            encoded: None,
+            fields: self.fields,
+            duration: self.duration,
+            text_color: self.text_color,
+            background_color: self.background_color,
+            callback: self.callback,
+            chain: self.chain,
+            user_data: self.user_data,
+            data_callback: self.data_callback,
+            keep_until_shown: self.keep_until_shown,
+            shake: None,
+            delay: self.delay,
+            append_elapsed: self.append_elapsed,
+            append_location: cfg!(debug_assertions) && ERROR_LOCATIONS.load(Ordering::Relaxed),
+            auto_duration: self.auto_duration,
+            filters: self.filters,
+            sequence: self.sequence,
+            sticky: self.sticky,
+            summarized: self.summarized,
+            _marker: PhantomData,
+        }
+        .apply_theme(theme)
+    }
 }
 
 impl NotificationBuilder<Error> {
@@ -390,6 +1515,34 @@ impl NotificationBuilder<Error> {
         self.shake = duration;
         self
     }
+
+    /// Append the [`caller`](Self::caller) to the text, e.g. `"Save failed (src/net.rs:214)"`.
+    ///
+    /// Defaults to [`error_locations`], only available in debug builds.
+    #[cfg(debug_assertions)]
+    pub fn location(mut self, enable: bool) -> Self {
+        self.append_location = enable;
+        self
+    }
+}
+
+#[inline]
+fn default_text_color() -> Color {
+    theme().text_color
+}
+
+#[inline]
+fn default_background_color() -> Color {
+    theme().background_color
+}
+
+/// OSThread currently running a finish callback, 0 if none.
+static CALLBACK_THREAD: AtomicUsize = AtomicUsize::new(0);
+
+/// The calling thread is running a finish callback.
+fn in_callback() -> bool {
+    let thread = unsafe { wut::bindings::OSGetCurrentThread() } as usize;
+    CALLBACK_THREAD.load(Ordering::Acquire) == thread
 }
 
 unsafe extern "C" fn notification_callback(
@@ -397,24 +1550,165 @@ unsafe extern "C" fn notification_callback(
     arg: *mut core::ffi::c_void,
 ) {
     if !arg.is_null() {
-        let closure = unsafe { Box::from_raw(arg as *mut Box<dyn FnOnce()>) };
-        closure();
+        run_callback(unsafe { Box::from_raw(arg as *mut Box<dyn FnOnce()>) });
     }
 }
 
+/// Runs a finish callback on the calling thread, notifications shown from it are deferred.
+fn run_callback(closure: Box<Box<dyn FnOnce()>>) {
+    let thread = unsafe { wut::bindings::OSGetCurrentThread() } as usize;
+    let previous = CALLBACK_THREAD.swap(thread, Ordering::AcqRel);
+    closure();
+    CALLBACK_THREAD.store(previous, Ordering::Release);
+}
+
 // endregion
 
+#[track_caller]
 pub fn dynamic(text: &str) -> NotificationBuilder<Dynamic> {
-    NotificationBuilder::<Dynamic>::default().text(text)
+    NotificationBuilder::<Dynamic>::new(text)
 }
 
+#[track_caller]
 pub fn info(text: &str) -> NotificationBuilder<Info> {
-    NotificationBuilder::<Info>::default().text(text)
+    NotificationBuilder::<Info>::new(text)
 }
 
+/// Fades out all live dynamic notifications after `delay` and clears the queue of the
+/// [`NotificationManager`].
+pub fn dismiss_all(delay: Duration) -> Result<(), NotificationError> {
+    let live = core::mem::take(&mut *LIVE.lock());
+    manager().clear();
+
+    // sticky notifications may outlive their handles
+    let _r = NOTIFY.acquire();
+
+    let mut result = Ok(());
+    for (_, handle) in live {
+        trace::call(
+            Operation::FinishDynamicNotification,
+            format_args!("{handle}, {delay:?}"),
+        );
+        let status = unsafe {
+            sys::NotificationModule_FinishDynamicNotification(handle, delay.as_secs_f32())
+        };
+        if let Err(e) = NotificationError::check(status, Operation::FinishDynamicNotification) {
+            result = result.and(Err(e));
+        }
+    }
+
+    result
+}
+
+/// Tears down all notifications in a controlled order, e.g. before the plugin is unloaded.
+///
+/// Clears the queue of the [`NotificationManager`], then finishes all live dynamic notifications
+/// immediately. Remaining [`Notification`] handles become inert, dropping them no longer calls
+/// into the module, so they may outlive the module safely.
+pub fn shutdown() -> Result<(), NotificationError> {
+    dismiss_all(Duration::ZERO)
+}
+
+/// Advances throttles, timeouts and queued notifications by `delta` from the caller's frame
+/// update instead of the system timer, and dispatches due notifications of the
+/// [`NotificationManager`].
+///
+/// The first call switches to tick mode until [`untick`]. Blocking waits such as
+/// [`wait_until_ready`] keep using the system timer.
+pub fn tick(delta: Duration) -> Result<(), NotificationError> {
+    clock::advance(delta);
+    manager().update()
+}
+
+/// Leaves tick mode, time is taken from the system timer again.
+pub fn untick() {
+    clock::untick();
+}
+
+/// The overlay can show notifications, e.g. once early title startup is over.
+///
+/// Always `true` below module API level 2, which can't report it.
+pub fn is_overlay_ready() -> bool {
+    if cfg!(not(feature = "module-api-2")) {
+        return true;
+    }
+
+    let _r = NOTIFY.acquire();
+    overlay_status().unwrap_or(false)
+}
+
+/// Blocks until [`is_overlay_ready`], [`Timeout`] after `timeout`.
+///
+/// [`Timeout`]: NotificationError::Timeout
+pub fn wait_until_ready(timeout: Duration) -> Result<(), NotificationError> {
+    let _r = NOTIFY.acquire();
+    let deadline = clock::real() + timeout;
+    while !is_overlay_ready() {
+        if clock::real() >= deadline {
+            return Err(NotificationError::Timeout);
+        }
+        unsafe { wut::bindings::OSYieldThread() };
+    }
+
+    Ok(())
+}
+
+/// Queries the overlay, the module must be initialized.
+#[cfg(feature = "module-api-2")]
+pub(crate) fn overlay_status() -> Result<bool, NotificationError> {
+    let mut ready = false;
+    trace::call(Operation::IsOverlayReady, format_args!(""));
+    let status = unsafe { sys::NotificationModule_IsOverlayReady(&mut ready) };
+    NotificationError::check(status, Operation::IsOverlayReady)?;
+    Ok(ready)
+}
+
+#[cfg(not(feature = "module-api-2"))]
+pub(crate) fn overlay_status() -> Result<bool, NotificationError> {
+    Err(NotificationError::UnsupportedCommand(
+        Operation::IsOverlayReady,
+    ))
+}
+
+/// Info styled notification which stays visible until it is acknowledged.
+///
+/// Unlike [`dynamic`] ones, dropping the returned [`Notification`] keeps it on screen, it is
+/// only removed by [`dismiss`](Notification::dismiss), [`finish`](Notification::finish) or
+/// [`dismiss_all`]. It is kept until the overlay is ready to show it.
+#[track_caller]
+pub fn sticky(text: &str) -> NotificationBuilder<Dynamic> {
+    let mut builder = NotificationBuilder::<Dynamic>::new(text).keep_until_shown(true);
+    builder.sticky = true;
+    builder
+}
+
+#[track_caller]
 pub fn error(text: &str) -> NotificationBuilder<Error> {
-    NotificationBuilder::<Error>::default()
-        .text(text)
-        .background_color(Color::red())
-        .shake(Some(Duration::from_secs(1)))
+    let mut builder = NotificationBuilder::<Error>::new(text).apply_theme(&theme());
+    builder.append_location = cfg!(debug_assertions) && ERROR_LOCATIONS.load(Ordering::Relaxed);
+    builder
+}
+
+static LOCATED_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Wrap errors of [`NotificationBuilder::show`] in [`Located`] with the caller which created the
+/// builder.
+///
+/// Disabled by default, so errors can be matched directly. Once enabled, match on
+/// [`kind`](NotificationError::kind), e.g. `matches!(e.kind(), NotificationError::Timeout)`.
+///
+/// [`Located`]: NotificationError::Located
+pub fn located_errors(enable: bool) {
+    LOCATED_ERRORS.store(enable, Ordering::Relaxed);
+}
+
+static ERROR_LOCATIONS: AtomicBool = AtomicBool::new(false);
+
+/// Append the source location to the text of [`error`] notifications created afterwards.
+///
+/// Disabled by default since errors raised by the crate itself would point into it. Only
+/// available in debug builds so release builds never show it.
+#[cfg(debug_assertions)]
+pub fn error_locations(enable: bool) {
+    ERROR_LOCATIONS.store(enable, Ordering::Relaxed);
 }
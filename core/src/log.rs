@@ -0,0 +1,72 @@
+use crate::{
+    logging::{self, Severity},
+    strict,
+};
+use ::log::{LevelFilter, Log, Metadata, Record};
+use alloc::{format, vec::Vec};
+
+/// [`log::Log`](::log::Log) implementation which shows records as notifications.
+///
+/// Records are shown according to the [`Route`](logging::Route) of their target or the
+/// [`LevelMap`](logging::LevelMap), so crates already using `log` surface their messages
+/// without changes.
+///
+/// ```ignore
+/// static LOGGER: NotificationLogger = NotificationLogger::new(log::LevelFilter::Warn);
+/// NotificationLogger::install(&LOGGER)?;
+/// ```
+pub struct NotificationLogger {
+    level: LevelFilter,
+}
+
+impl Default for NotificationLogger {
+    fn default() -> Self {
+        Self::new(LevelFilter::Trace)
+    }
+}
+
+impl NotificationLogger {
+    /// Logger passing records of `level` and above to the [`LevelMap`](logging::LevelMap).
+    pub const fn new(level: LevelFilter) -> Self {
+        Self { level }
+    }
+
+    /// Sets `logger` as the global logger and raises the max level of `log` to its level.
+    pub fn install(logger: &'static Self) -> Result<(), ::log::SetLoggerError> {
+        ::log::set_logger(logger)?;
+        ::log::set_max_level(logger.level);
+        Ok(())
+    }
+}
+
+impl Log for NotificationLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let severity = match record.level() {
+            ::log::Level::Error => Severity::Error,
+            ::log::Level::Warn => Severity::Warn,
+            ::log::Level::Info => Severity::Info,
+            ::log::Level::Debug => Severity::Debug,
+            ::log::Level::Trace => Severity::Trace,
+        };
+
+        // a logger has no way to report errors
+        strict::ignore(logging::dispatch(
+            severity,
+            record.target(),
+            &format!("{}", record.args()),
+            Vec::new(),
+        ));
+    }
+
+    fn flush(&self) {
+        strict::ignore(logging::flush());
+    }
+}
@@ -0,0 +1,438 @@
+use crate::{
+    Info, Level, NotificationBuilder, NotificationError, Theme, clock, info,
+    manager::{self, NotificationSpec},
+    summary,
+};
+use alloc::{format, string::String, vec::Vec};
+use core::time::Duration;
+use spin::Mutex;
+use wut::gx2::color::Color;
+
+static LEVEL_MAP: Mutex<Option<LevelMap>> = Mutex::new(None);
+static SAMPLES: Mutex<Vec<Sample>> = Mutex::new(Vec::new());
+static ROUTES: Mutex<Vec<Route>> = Mutex::new(Vec::new());
+static BURST: Mutex<Burst> = Mutex::new(Burst {
+    limit: Some(5),
+    window_start: Duration::ZERO,
+    count: 0,
+    suppressed: 0,
+});
+
+/// Sampling rate of a target.
+struct Sample {
+    target: String,
+    n: u32,
+    seen: u32,
+}
+
+/// Records shown within the current one second window.
+struct Burst {
+    limit: Option<u32>,
+    window_start: Duration,
+    count: u32,
+    suppressed: u32,
+}
+
+impl Burst {
+    /// Counts a record, returns `false` if it exceeds the limit.
+    fn admit(&mut self) -> bool {
+        self.count += 1;
+        match self.limit {
+            Some(limit) if self.count > limit => {
+                self.suppressed += 1;
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Starts a new window if the current one is over, returns the records suppressed in it.
+    fn roll(&mut self, now: Duration) -> u32 {
+        if now.saturating_sub(self.window_start) < Duration::from_secs(1) {
+            return 0;
+        }
+
+        self.window_start = now;
+        self.count = 0;
+        core::mem::take(&mut self.suppressed)
+    }
+}
+
+/// Restores the default level map and burst limit, removes all routes and sampling rates.
+#[cfg(feature = "mock")]
+pub(crate) fn reset() {
+    *BURST.lock() = Burst {
+        limit: Some(5),
+        window_start: Duration::ZERO,
+        count: 0,
+        suppressed: 0,
+    };
+    *LEVEL_MAP.lock() = None;
+    SAMPLES.lock().clear();
+    ROUTES.lock().clear();
+}
+
+/// Severity of a record of a logging framework.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Severity {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// How records of a [`Severity`] are shown.
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    /// Not shown.
+    Suppress,
+    /// Shown as [`info`] notification, colors default to the active [`Theme`](crate::Theme).
+    Info {
+        text_color: Option<Color>,
+        background_color: Option<Color>,
+    },
+    /// Shown as [`error`] notification, `shake` defaults to the active [`Theme`](crate::Theme).
+    Error { shake: Option<Duration> },
+}
+
+/// Mapping from [`Severity`] to [`Action`] shared by all logging integrations.
+///
+/// The [`Default`] shows errors as shaking error notifications, warnings as amber info
+/// notifications and suppresses everything below.
+#[derive(Debug, Clone, Copy)]
+pub struct LevelMap {
+    pub error: Action,
+    pub warn: Action,
+    pub info: Action,
+    pub debug: Action,
+    pub trace: Action,
+}
+
+impl Default for LevelMap {
+    fn default() -> Self {
+        Self {
+            error: Action::Error { shake: None },
+            warn: Action::Info {
+                text_color: Some(Color::black()),
+                background_color: Some(Color {
+                    r: 0xFF,
+                    g: 0xBF,
+                    b: 0x00,
+                    a: 0xFF,
+                }),
+            },
+            info: Action::Suppress,
+            debug: Action::Suppress,
+            trace: Action::Suppress,
+        }
+    }
+}
+
+impl LevelMap {
+    pub fn action(&self, severity: Severity) -> Action {
+        match severity {
+            Severity::Error => self.error,
+            Severity::Warn => self.warn,
+            Severity::Info => self.info,
+            Severity::Debug => self.debug,
+            Severity::Trace => self.trace,
+        }
+    }
+}
+
+impl Action {
+    /// Notification for a record built as `builder`, `None` if the action suppresses it.
+    ///
+    /// `builder` is turned into an error notification for [`Action::Error`]. `theme` replaces
+    /// the styling of the active [`Theme`](crate::Theme), the colors and shake of the action
+    /// take precedence.
+    pub(crate) fn apply(
+        self,
+        builder: NotificationBuilder<Info>,
+        theme: Option<&Theme>,
+    ) -> Option<NotificationSpec> {
+        match self {
+            Action::Suppress => None,
+            Action::Info {
+                text_color,
+                background_color,
+            } => {
+                let mut builder = match theme {
+                    Some(theme) => builder.apply_theme(theme),
+                    None => builder,
+                };
+                if let Some(color) = text_color {
+                    builder = builder.text_color(color);
+                }
+                if let Some(color) = background_color {
+                    builder = builder.background_color(color);
+                }
+                Some(builder.into())
+            }
+            Action::Error { shake } => {
+                let mut builder = match theme {
+                    Some(theme) => builder.into_error(theme),
+                    None => builder.into_error(&crate::theme()),
+                };
+                if shake.is_some() {
+                    builder = builder.shake(shake);
+                }
+                Some(builder.into())
+            }
+        }
+    }
+}
+
+/// Currently active mapping.
+pub fn level_map() -> LevelMap {
+    LEVEL_MAP.lock().unwrap_or_default()
+}
+
+/// Replaces the mapping used by all logging integrations.
+pub fn set_level_map(map: LevelMap) {
+    *LEVEL_MAP.lock() = Some(map);
+}
+
+/// Shows only every `n`th record of `target` and its sub-targets (`"{target}::…"`), starting
+/// with the first. `n` of 0 or 1 removes sampling for the target.
+pub fn sample_1_in(target: &str, n: u32) {
+    let mut samples = SAMPLES.lock();
+    samples.retain(|s| s.target != target);
+    if n > 1 {
+        samples.push(Sample {
+            target: String::from(target),
+            n,
+            seen: 0,
+        });
+    }
+}
+
+/// `target` is `prefix` or one of its sub-targets.
+fn within(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Counts a record of `target`, returns `false` if it is not sampled.
+fn sampled(target: &str) -> bool {
+    let mut samples = SAMPLES.lock();
+    let sample = samples.iter_mut().find(|s| within(target, &s.target));
+
+    match sample {
+        Some(sample) => {
+            let shown = sample.seen % sample.n == 0;
+            sample.seen = sample.seen.wrapping_add(1);
+            shown
+        }
+        None => true,
+    }
+}
+
+/// Handling of the records of a target and its sub-targets (`"{target}::…"`), see
+/// [`set_routes`].
+///
+/// ```ignore
+/// logging::set_routes([
+///     Route { target: "net".into(), theme: Some(BLUE), ..Default::default() },
+///     Route { target: "save".into(), always: true, ..Default::default() },
+/// ]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Route {
+    /// Target the route applies to.
+    pub target: String,
+    /// [`tag`](crate::NotificationBuilder::tag) of the notifications, matched by the rules and
+    /// mutes of the [`NotificationManager`](crate::NotificationManager).
+    pub tag: Option<&'static str>,
+    /// Mapping replacing the global [`LevelMap`] for the target.
+    pub levels: Option<LevelMap>,
+    /// Styling of the notifications, colors of the [`Action`] take precedence.
+    pub theme: Option<Theme>,
+    /// Show every record, bypassing sampling and burst protection. Suppressed severities are
+    /// shown as info notifications.
+    pub always: bool,
+}
+
+/// Replaces the routing table, the first route matching the target of a record applies.
+/// Records of other targets use the global [`LevelMap`].
+pub fn set_routes(routes: impl IntoIterator<Item = Route>) {
+    *ROUTES.lock() = routes.into_iter().collect();
+}
+
+/// First route matching `target`.
+fn route(target: &str) -> Option<Route> {
+    ROUTES
+        .lock()
+        .iter()
+        .find(|route| within(target, &route.target))
+        .cloned()
+}
+
+/// Maximum number of records shown per second, `None` disables burst protection.
+///
+/// Records beyond the limit are collapsed into a single `"N messages suppressed (see log)"`
+/// notification. Defaults to 5.
+pub fn set_burst_limit(limit: Option<u32>) {
+    BURST.lock().limit = limit;
+}
+
+/// Shows the notification for records suppressed by burst protection, if any.
+pub fn flush() -> Result<(), NotificationError> {
+    let suppressed = core::mem::take(&mut BURST.lock().suppressed);
+    match notice(suppressed) {
+        Some(notice) => notice.show().map(drop),
+        None => Ok(()),
+    }
+}
+
+/// Ends the burst window once it is over, returns the notification for the records suppressed
+/// in it. Polled by [`NotificationManager::update`](crate::NotificationManager::update), so the
+/// notification doesn't wait for the next record.
+pub(crate) fn poll() -> Option<NotificationBuilder<Info>> {
+    let suppressed = BURST.lock().roll(clock::now());
+    notice(suppressed)
+}
+
+fn notice(suppressed: u32) -> Option<NotificationBuilder<Info>> {
+    (suppressed > 0).then(|| info(&format!("{suppressed} messages suppressed (see log)")))
+}
+
+/// Submits a record of `target` to the [`manager`](crate::manager()) according to its [`Route`]
+/// or the active [`LevelMap`], it is shown on the next update.
+pub fn dispatch(
+    severity: Severity,
+    target: &str,
+    text: &str,
+    fields: Vec<(&'static str, String)>,
+) -> Result<(), NotificationError> {
+    let route = route(target).unwrap_or_default();
+    let action = match route.levels {
+        Some(levels) => levels.action(severity),
+        None => level_map().action(severity),
+    };
+    let action = match action {
+        Action::Suppress if route.always => Action::Info {
+            text_color: None,
+            background_color: None,
+        },
+        action => action,
+    };
+
+    // counted once at the logged severity, also if shown as error notification
+    match severity {
+        Severity::Error => summary::record(Level::Error),
+        Severity::Warn => summary::record(Level::Warn),
+        _ => {}
+    }
+
+    if matches!(action, Action::Suppress) {
+        return Ok(());
+    }
+    if !route.always {
+        if !sampled(target) {
+            return Ok(());
+        }
+
+        let (suppressed, admitted) = {
+            let mut burst = BURST.lock();
+            let now = clock::now();
+            (burst.roll(now), burst.admit())
+        };
+        if let Some(notice) = notice(suppressed) {
+            manager::submit(notice.into());
+        }
+        if !admitted {
+            return Ok(());
+        }
+    }
+
+    let mut builder = info(text);
+    builder.summarized = true;
+    if let Some(tag) = route.tag {
+        builder = builder.tag(tag);
+    }
+    for (key, value) in fields {
+        builder = builder.field(key, value);
+    }
+    if let Some(spec) = action.apply(builder, route.theme.as_ref()) {
+        manager::submit(spec);
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::manager::manager;
+    use crate::mock::{Call, MockBackend, Simulation};
+
+    #[test]
+    fn muted_route() {
+        let _sim = Simulation::start();
+        set_routes([Route {
+            target: String::from("net"),
+            tag: Some("net"),
+            ..Default::default()
+        }]);
+        manager().mute("net");
+
+        dispatch(Severity::Error, "net::http", "down", Vec::new()).unwrap();
+        dispatch(Severity::Error, "save", "failed", Vec::new()).unwrap();
+        manager().update().unwrap();
+
+        let texts: Vec<_> = MockBackend::calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Error { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts, ["failed"]);
+    }
+
+    #[test]
+    fn summarized_once() {
+        let _sim = Simulation::start();
+        set_routes([Route {
+            target: String::from("net"),
+            levels: Some(LevelMap {
+                warn: Action::Error { shake: None },
+                ..Default::default()
+            }),
+            ..Default::default()
+        }]);
+        summary::enable();
+
+        dispatch(Severity::Warn, "net", "slow", Vec::new()).unwrap();
+        dispatch(Severity::Error, "save", "failed", Vec::new()).unwrap();
+        manager().update().unwrap();
+
+        assert_eq!(summary::counts(), (1, 1));
+    }
+
+    #[test]
+    fn burst_notice() {
+        let sim = Simulation::start();
+        set_burst_limit(Some(1));
+        summary::enable();
+
+        for _ in 0..3 {
+            dispatch(Severity::Error, "net", "down", Vec::new()).unwrap();
+        }
+        sim.advance(Duration::from_millis(1500)).unwrap();
+
+        let texts: Vec<_> = MockBackend::calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Info { text, .. } | Call::Error { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts[..2], ["down", "2 messages suppressed (see log)"]);
+        assert_eq!(summary::counts(), (0, 3));
+    }
+}
@@ -0,0 +1,38 @@
+/// Formats and shows an [`info`](crate::info) notification, returning the result of
+/// [`show`](crate::NotificationBuilder::show).
+///
+/// ```ignore
+/// notify_info!("Loaded {count} mods")?;
+/// ```
+#[macro_export]
+macro_rules! notify_info {
+    ($($arg:tt)*) => {
+        $crate::info(&$crate::__format!($($arg)*)).show()
+    };
+}
+
+/// Formats and shows an [`error`](crate::error) notification, returning the result of
+/// [`show`](crate::NotificationBuilder::show).
+///
+/// ```ignore
+/// notify_error!("Save failed: {err}")?;
+/// ```
+#[macro_export]
+macro_rules! notify_error {
+    ($($arg:tt)*) => {
+        $crate::error(&$crate::__format!($($arg)*)).show()
+    };
+}
+
+/// Formats and shows a [`dynamic`](crate::dynamic) notification, returning the
+/// [`Notification`](crate::Notification) handle.
+///
+/// ```ignore
+/// let progress = notify_dynamic!("Downloading {name}")?;
+/// ```
+#[macro_export]
+macro_rules! notify_dynamic {
+    ($($arg:tt)*) => {
+        $crate::dynamic(&$crate::__format!($($arg)*)).show()
+    };
+}
@@ -0,0 +1,958 @@
+use crate::{
+    Dynamic, Error, Info, LIVE, NOTIFY, Notification, NotificationBuilder, NotificationError,
+    Theme, clock, error, history, info, is_overlay_ready,
+    logging::{self, Severity, level_map},
+    policy::{Coordinator, Policy, Rule, Sink, Verdict},
+    ring, stats, watchdog,
+};
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+use core::{ffi::CStr, fmt, time::Duration};
+use spin::{Mutex, MutexGuard};
+use wut::rrc::RrcGuard;
+
+static MANAGER: Mutex<NotificationManager> = Mutex::new(NotificationManager::new());
+/// Shown from finish callbacks, which must not lock the manager.
+static DEFERRED: Mutex<Deferred> = Mutex::new(Deferred(Vec::new()));
+
+struct Deferred(Vec<NotificationSpec>);
+
+unsafe impl Send for Deferred {}
+
+pub(crate) fn defer(spec: NotificationSpec) {
+    DEFERRED.lock().0.push(spec);
+}
+
+/// Global notification manager.
+pub fn manager() -> MutexGuard<'static, NotificationManager> {
+    MANAGER.lock()
+}
+
+/// Submits `spec` to the manager, deferred to the next update if the manager is locked, e.g.
+/// by a record logged while it dispatches.
+pub(crate) fn submit(spec: NotificationSpec) {
+    match MANAGER.try_lock() {
+        Some(mut manager) => {
+            manager.submit(spec);
+        }
+        None => defer(spec),
+    }
+}
+
+// region: NotificationSpec
+
+/// Identifies a notification submitted to the [`NotificationManager`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Id(u64);
+
+/// Severity of a notification, higher levels are dispatched first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+/// Notification waiting to be dispatched by the [`NotificationManager`].
+pub enum NotificationSpec {
+    Info(NotificationBuilder<Info>),
+    Error(NotificationBuilder<Error>),
+    Dynamic(NotificationBuilder<Dynamic>),
+}
+
+impl NotificationSpec {
+    /// Severity derived from the notification type.
+    pub fn level(&self) -> Level {
+        match self {
+            Self::Info(_) | Self::Dynamic(_) => Level::Info,
+            Self::Error(_) => Level::Error,
+        }
+    }
+
+    /// Highest level of the notification and the ones chained with
+    /// [`then`](NotificationBuilder::then).
+    pub fn chain_level(&self) -> Level {
+        let chain = match self {
+            Self::Info(builder) => builder.chain,
+            Self::Error(builder) => builder.chain,
+            Self::Dynamic(builder) => builder.chain,
+        };
+        self.level().max(chain.unwrap_or(Level::Info))
+    }
+
+    fn tag(&self) -> Option<&'static str> {
+        match self {
+            Self::Info(builder) => builder.tag,
+            Self::Error(builder) => builder.tag,
+            Self::Dynamic(builder) => builder.tag,
+        }
+    }
+
+    fn apply_theme(self, theme: &Theme) -> Self {
+        match self {
+            Self::Info(builder) => Self::Info(builder.apply_theme(theme)),
+            Self::Error(builder) => Self::Error(builder.apply_theme(theme)),
+            Self::Dynamic(builder) => Self::Dynamic(builder.apply_theme(theme)),
+        }
+    }
+
+    /// Shows the notification right away, bypassing the queue.
+    pub(crate) fn show(self) -> Result<(), NotificationError> {
+        match self {
+            Self::Info(builder) => builder.show().map(drop),
+            Self::Error(builder) => builder.show().map(drop),
+            Self::Dynamic(builder) => builder.show().map(drop),
+        }
+    }
+
+    fn encode(&mut self) {
+        match self {
+            Self::Info(builder) => builder.encode(),
+            Self::Error(builder) => builder.encode(),
+            Self::Dynamic(builder) => builder.encode(),
+        }
+    }
+
+    fn heap_size(&self) -> usize {
+        match self {
+            Self::Info(builder) => builder.heap_size(),
+            Self::Error(builder) => builder.heap_size(),
+            Self::Dynamic(builder) => builder.heap_size(),
+        }
+    }
+
+    /// Complete text, encoded once and kept for showing the notification.
+    fn text(&mut self) -> Option<String> {
+        match self {
+            Self::Info(builder) => builder.encoded_text(),
+            Self::Error(builder) => builder.encoded_text(),
+            Self::Dynamic(builder) => builder.encoded_text(),
+        }
+    }
+}
+
+impl From<NotificationBuilder<Info>> for NotificationSpec {
+    fn from(value: NotificationBuilder<Info>) -> Self {
+        Self::Info(value)
+    }
+}
+
+impl From<NotificationBuilder<Error>> for NotificationSpec {
+    fn from(value: NotificationBuilder<Error>) -> Self {
+        Self::Error(value)
+    }
+}
+
+impl From<NotificationBuilder<Dynamic>> for NotificationSpec {
+    fn from(value: NotificationBuilder<Dynamic>) -> Self {
+        Self::Dynamic(value)
+    }
+}
+
+// endregion
+
+// region: NotificationManager
+
+pub(crate) struct Pending {
+    id: Id,
+    level: Level,
+    spec: NotificationSpec,
+    submitted: Duration,
+    not_before: Duration,
+}
+
+struct Active {
+    id: Id,
+    level: Level,
+    notification: Notification,
+    template: NotificationBuilder<Dynamic>,
+}
+
+/// Queues notifications and dispatches them to the overlay on [`update`](Self::update).
+pub struct NotificationManager {
+    next_id: u64,
+    pending: VecDeque<Pending>,
+    active: Vec<Active>,
+    preempt_dynamics: bool,
+    inherit_priority: bool,
+    max_age: Option<Duration>,
+    /// Maximum dispatches per window, see [`burst_limit`](Self::burst_limit).
+    burst: Option<(u32, Duration)>,
+    burst_start: Duration,
+    burst_count: u32,
+    policy: Policy,
+    line: String,
+    line_prefixes: Vec<(&'static str, Severity)>,
+    /// Moving average of the display duration of dispatched notifications.
+    shown_duration: Option<Duration>,
+    /// Keeps the module initialized after [`warm_up`](Self::warm_up).
+    warm: Option<RrcGuard>,
+    history: VecDeque<history::Entry>,
+    history_capacity: usize,
+    heap_budget: Option<usize>,
+}
+
+impl NotificationManager {
+    const fn new() -> Self {
+        Self {
+            next_id: 0,
+            pending: VecDeque::new(),
+            active: Vec::new(),
+            preempt_dynamics: false,
+            inherit_priority: false,
+            max_age: None,
+            burst: None,
+            burst_start: Duration::ZERO,
+            burst_count: 0,
+            policy: Policy::new(),
+            line: String::new(),
+            line_prefixes: Vec::new(),
+            shown_duration: None,
+            warm: None,
+            history: VecDeque::new(),
+            history_capacity: 0,
+            heap_budget: None,
+        }
+    }
+
+    /// Replaces the rules evaluated for every tagged submission.
+    ///
+    /// The first rule matching the tag of a submission applies.
+    pub fn set_rules(&mut self, rules: impl IntoIterator<Item = Rule>) {
+        self.policy.set_rules(rules);
+    }
+
+    /// Rules evaluated for every tagged submission.
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.policy.rules()
+    }
+
+    /// Drops all submissions with the given tag, e.g. from a settings page of the plugin.
+    ///
+    /// Mutes are kept across sessions by storing the text of [`save_mutes`](Self::save_mutes),
+    /// e.g. in a file or the WUPS storage, and passing it to [`load_mutes`](Self::load_mutes).
+    pub fn mute(&mut self, tag: &str) {
+        self.policy.mute(tag);
+    }
+
+    pub fn unmute(&mut self, tag: &str) {
+        self.policy.unmute(tag);
+    }
+
+    pub fn is_muted(&self, tag: &str) -> bool {
+        self.policy.is_muted(tag)
+    }
+
+    /// Muted tags in the order they were muted.
+    pub fn muted(&self) -> impl Iterator<Item = &str> {
+        self.policy.muted()
+    }
+
+    /// Writes the muted tags to `out`, one per line, e.g. to store them in the settings of the
+    /// plugin.
+    pub fn save_mutes(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        for tag in self.muted() {
+            out.write_str(tag)?;
+            out.write_char('\n')?;
+        }
+        Ok(())
+    }
+
+    /// Mutes the tags written by [`save_mutes`](Self::save_mutes), blank lines are skipped.
+    pub fn load_mutes(&mut self, text: &str) {
+        for tag in text.lines().map(str::trim).filter(|t| !t.is_empty()) {
+            self.mute(tag);
+        }
+    }
+
+    /// Drops all submissions below [`Level::Error`] until it is unset, e.g. from a settings page
+    /// of the plugin. See [`Preferences`](crate::prefs::Preferences) to keep it across sessions.
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.policy.set_quiet(quiet);
+    }
+
+    pub fn is_quiet(&self) -> bool {
+        self.policy.is_quiet()
+    }
+
+    /// Shares rate limits and quiet mode with other plugins, see [`Coordinator`].
+    pub fn set_coordinator(&mut self, coordinator: Option<&'static dyn Coordinator>) {
+        self.policy.set_coordinator(coordinator);
+    }
+
+    /// Receives the text of submissions rerouted by a [`Rule`], without a sink they are dropped.
+    pub fn set_sink(&mut self, sink: Option<Sink>) {
+        self.policy.set_sink(sink);
+    }
+
+    pub(crate) fn sink(&self) -> Option<Sink> {
+        self.policy.sink()
+    }
+
+    /// Sets the sink and, with `replay`, passes it the [`history`](Self::history), so tooling
+    /// attached late still sees what happened during boot.
+    pub fn attach_sink(&mut self, sink: Sink, replay: bool) {
+        self.policy.set_sink(Some(sink));
+        if replay {
+            for entry in &self.history {
+                sink(entry.level, &entry.text);
+            }
+        }
+    }
+
+    /// Remember the text of the last `capacity` notifications shown or rerouted, 0 disables the
+    /// history.
+    ///
+    /// Texts are recorded when the notification is shown, so lazy texts of notifications which
+    /// are dropped while queued are never built.
+    pub fn keep_history(&mut self, capacity: usize) {
+        self.history_capacity = capacity;
+        while self.history.len() > capacity {
+            self.history.pop_front();
+        }
+    }
+
+    /// Texts of the last notifications shown or rerouted, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = (Level, &str)> {
+        self.history
+            .iter()
+            .map(|entry| (entry.level, entry.text.as_str()))
+    }
+
+    /// Recorded notifications with their time, tag and outcome, oldest first, see
+    /// [`history::export`](crate::history::export).
+    pub fn history_entries(&self) -> impl Iterator<Item = &history::Entry> {
+        self.history.iter()
+    }
+
+    fn push_history(
+        &mut self,
+        level: Level,
+        tag: Option<&'static str>,
+        text: &str,
+        outcome: history::Outcome,
+    ) {
+        if self.history_capacity == 0 {
+            return;
+        }
+        if self.history.len() == self.history_capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(history::Entry {
+            time: clock::now(),
+            level,
+            tag,
+            text: String::from(text),
+            outcome,
+        });
+    }
+
+    /// Initializes the module and reserves room for `capacity` notifications, so the first
+    /// notification during gameplay doesn't cause a hitch.
+    ///
+    /// Call from a loading screen or a background thread. The module stays initialized until
+    /// [`cool_down`](Self::cool_down).
+    pub fn warm_up(&mut self, capacity: usize) {
+        self.warm.get_or_insert_with(|| NOTIFY.acquire());
+        self.pending.reserve(capacity);
+        self.active.reserve(capacity);
+        LIVE.lock().reserve(capacity);
+    }
+
+    /// Releases the module initialized by [`warm_up`](Self::warm_up).
+    pub fn cool_down(&mut self) {
+        self.warm = None;
+    }
+
+    /// Drop queued notifications which could not be shown within `max_age`, e.g. because the
+    /// overlay was not ready yet.
+    pub fn max_age(&mut self, max_age: Option<Duration>) {
+        self.max_age = max_age;
+    }
+
+    /// Dispatch at most `max` notifications per `window`, so bursts don't flood the screen.
+    ///
+    /// Notifications over the limit stay queued for the next window, errors first.
+    pub fn burst_limit(&mut self, limit: Option<(u32, Duration)>) {
+        self.burst = limit;
+    }
+
+    /// Limit the estimated heap usage of queued notifications, the history and the line buffer
+    /// to `bytes`, e.g. on memory-constrained titles.
+    ///
+    /// Over budget, the history is shrunk first, then the newest queued notifications below
+    /// [`Level::Error`] are dropped. The usage is reported in [`Stats::heap`](stats::Stats::heap).
+    pub fn heap_budget(&mut self, bytes: Option<usize>) {
+        self.heap_budget = bytes;
+        self.enforce_budget();
+    }
+
+    /// Estimated heap usage in bytes.
+    pub fn heap_usage(&self) -> usize {
+        let pending = self
+            .pending
+            .iter()
+            .map(|p| core::mem::size_of::<Pending>() + p.spec.heap_size())
+            .sum::<usize>();
+        let history = self
+            .history
+            .iter()
+            .map(|entry| core::mem::size_of::<history::Entry>() + entry.text.capacity())
+            .sum::<usize>();
+        let active = self.active.capacity() * core::mem::size_of::<Active>();
+        pending + history + active + self.line.capacity()
+    }
+
+    fn enforce_budget(&mut self) {
+        let mut usage = self.heap_usage();
+        if let Some(budget) = self.heap_budget {
+            while usage > budget && self.history.pop_front().is_some() {
+                usage = self.heap_usage();
+            }
+            while usage > budget {
+                // the queue is ordered by level, so the back holds the newest of the lowest
+                if !self.pending.back().is_some_and(|p| p.level < Level::Error) {
+                    break;
+                }
+                self.pending.pop_back();
+                stats::dropped(1);
+                usage = self.heap_usage();
+            }
+        }
+        stats::heap(usage);
+    }
+
+    /// Queue chains built with [`then`](NotificationBuilder::then) with the highest level in
+    /// the chain, e.g. an info chained before an error is dispatched like an error.
+    pub fn inherit_priority(&mut self, inherit: bool) {
+        self.inherit_priority = inherit;
+    }
+
+    /// Fade out live dynamic notifications of a lower level when an error is dispatched.
+    pub fn preempt_dynamics(&mut self, preempt: bool) {
+        self.preempt_dynamics = preempt;
+    }
+
+    /// Queues a notification to be shown on the next [`update`](Self::update).
+    ///
+    /// Notifications are queued behind all pending notifications of the same or a higher level.
+    ///
+    /// Returns `None` if the notification was dropped or rerouted by a [`Rule`].
+    pub fn submit(&mut self, spec: impl Into<NotificationSpec>) -> Option<Id> {
+        let (level, spec) = self.admit(spec.into())?;
+        Some(self.queue(level, spec))
+    }
+
+    /// Queues several notifications at once, e.g. the messages of a startup sequence.
+    ///
+    /// The module stays initialized for the whole batch and the texts of all admitted
+    /// notifications are encoded before the first one is queued.
+    ///
+    /// Returns the ids in order, `None` for notifications dropped or rerouted by a [`Rule`].
+    pub fn submit_all<S: Into<NotificationSpec>>(
+        &mut self,
+        specs: impl IntoIterator<Item = S>,
+    ) -> Vec<Option<Id>> {
+        let _r = NOTIFY.acquire();
+        let admitted: Vec<_> = specs
+            .into_iter()
+            .map(|spec| {
+                let (level, mut spec) = self.admit(spec.into())?;
+                spec.encode();
+                Some((level, spec))
+            })
+            .collect();
+
+        admitted
+            .into_iter()
+            .map(|admitted| admitted.map(|(level, spec)| self.queue(level, spec)))
+            .collect()
+    }
+
+    /// Evaluates the rules for a submission, returns its level and the styled spec if it is
+    /// shown.
+    fn admit(&mut self, mut spec: NotificationSpec) -> Option<(Level, NotificationSpec)> {
+        let level = if self.inherit_priority {
+            spec.chain_level()
+        } else {
+            spec.level()
+        };
+
+        match self.policy.evaluate(spec.tag(), level) {
+            Verdict::Show(_) if !self.policy.coordinate(spec.tag(), level) => {
+                stats::dropped(1);
+                None
+            }
+            Verdict::Show(theme) => {
+                if let Some(theme) = theme {
+                    spec = spec.apply_theme(&theme);
+                }
+                Some((level, spec))
+            }
+            Verdict::Reroute => {
+                if let Some(text) = spec.text() {
+                    self.push_history(level, spec.tag(), &text, history::Outcome::Rerouted);
+                    if let Some(sink) = self.policy.sink() {
+                        sink(level, &text);
+                    }
+                }
+                None
+            }
+            Verdict::Drop => {
+                stats::dropped(1);
+                None
+            }
+        }
+    }
+
+    fn queue(&mut self, level: Level, spec: NotificationSpec) -> Id {
+        let id = Id(self.next_id);
+        self.next_id += 1;
+
+        self.enqueue(Pending {
+            id,
+            level,
+            spec,
+            submitted: clock::now(),
+            not_before: Duration::ZERO,
+        });
+        self.enforce_budget();
+
+        id
+    }
+
+    fn enqueue(&mut self, pending: Pending) {
+        let index = self
+            .pending
+            .iter()
+            .position(|p| p.level < pending.level)
+            .unwrap_or(self.pending.len());
+        self.pending.insert(index, pending);
+    }
+
+    /// Shows every queued notification which is due, including the ones shown from finish
+    /// callbacks, queued with [`raw_enqueue`](crate::raw_enqueue) or suppressed by the burst
+    /// protection of [`logging`] since the last update, and raises the errors of expired
+    /// [`Watchdog`](crate::Watchdog)s.
+    ///
+    /// Notifications stay queued while the overlay is not ready, so messages submitted during
+    /// early boot are shown once it is.
+    ///
+    /// A notification the module rejects is dropped and the rest of the batch still shown, the
+    /// first error is returned afterwards. Notifications rejected because the overlay isn't ready
+    /// or out of memory stay queued and are retried on the next update.
+    pub fn update(&mut self) -> Result<(), NotificationError> {
+        let deferred = core::mem::take(&mut DEFERRED.lock().0);
+        for spec in deferred {
+            self.submit(spec);
+        }
+        if let Some(notice) = logging::poll() {
+            self.submit(notice);
+        }
+        for (level, text) in ring::drain() {
+            if let Some(spec) = raw_spec(level, text) {
+                self.submit(spec);
+            }
+        }
+        watchdog::poll(self);
+
+        let now = clock::now();
+
+        if let Some(max_age) = self.max_age {
+            let queued = self.pending.len();
+            self.pending
+                .retain(|p| now.saturating_sub(p.submitted) <= max_age);
+            stats::dropped(queued - self.pending.len());
+        }
+
+        if self.pending.is_empty() || !is_overlay_ready() {
+            return Ok(());
+        }
+
+        // keeps the module initialized while dispatching the batch
+        let _r = NOTIFY.acquire();
+
+        // entries failing permanently are dropped, the rest of the batch is still shown
+        let mut result = Ok(());
+        while let Some(index) = self.pending.iter().position(|p| p.not_before <= now) {
+            if let Some((max, window)) = self.burst {
+                if now.saturating_sub(self.burst_start) >= window {
+                    self.burst_start = now;
+                    self.burst_count = 0;
+                }
+                if self.burst_count >= max {
+                    break;
+                }
+                self.burst_count += 1;
+            }
+
+            let Pending {
+                id,
+                level,
+                mut spec,
+                submitted,
+                not_before,
+            } = self.pending.remove(index).unwrap();
+
+            if self.preempt_dynamics && level == Level::Error {
+                for active in self.active.extract_if(.., |a| a.level < level) {
+                    active.notification.dismiss();
+                }
+            }
+
+            // built before showing, so the text is encoded once for both
+            let text = (self.history_capacity > 0).then(|| spec.text()).flatten();
+            let shown = match &mut spec {
+                NotificationSpec::Info(builder) => {
+                    let duration = builder.duration;
+                    builder.try_show().map(|_| self.observe(duration))
+                }
+                NotificationSpec::Error(builder) => {
+                    let duration = builder.duration;
+                    builder.try_show().map(|_| self.observe(duration))
+                }
+                NotificationSpec::Dynamic(builder) => {
+                    let template = builder.detached();
+                    builder.try_show().map(|notification| {
+                        self.active.push(Active {
+                            id,
+                            level,
+                            notification,
+                            template,
+                        })
+                    })
+                }
+            };
+
+            if let (Ok(()), Some(text)) = (&shown, &text) {
+                self.push_history(level, spec.tag(), text, history::Outcome::Shown);
+            }
+            if let Err(e) = shown {
+                let retry = matches!(
+                    e.kind(),
+                    NotificationError::OverlayNotReady(_) | NotificationError::AllocationFailed(_)
+                );
+                result = result.and(Err(e));
+                if retry {
+                    // the following entries would fail the same way, retry on the next update
+                    self.pending.insert(
+                        index,
+                        Pending {
+                            id,
+                            level,
+                            spec,
+                            submitted,
+                            not_before,
+                        },
+                    );
+                    self.burst_count = self.burst_count.saturating_sub(1);
+                    break;
+                }
+            }
+        }
+        stats::heap(self.heap_usage());
+
+        result
+    }
+
+    fn observe(&mut self, duration: Duration) {
+        self.shown_duration = Some(match self.shown_duration {
+            Some(average) => (average * 3 + duration) / 4,
+            None => duration,
+        });
+    }
+
+    /// Rough time until a notification submitted now would reach the screen, e.g. to decide
+    /// between notifying and only logging.
+    ///
+    /// Assumes every queued notification is shown for the average display duration observed so
+    /// far (the theme duration before the first dispatch), one after another.
+    pub fn estimated_wait(&self) -> Duration {
+        let now = clock::now();
+        let average = self
+            .shown_duration
+            .unwrap_or_else(|| crate::theme().duration);
+        let queued = average * self.pending.len() as u32;
+        let postponed = self
+            .pending
+            .iter()
+            .map(|p| p.not_before.saturating_sub(now))
+            .max()
+            .unwrap_or_default();
+
+        queued.max(postponed)
+    }
+
+    /// Removes all queued and live notifications.
+    pub fn clear(&mut self) {
+        DEFERRED.lock().0.clear();
+        self.pending.clear();
+        self.active.clear();
+    }
+
+    /// Takes the queued notifications, including the deferred ones, e.g. to keep them across a
+    /// [`shutdown`](crate::shutdown).
+    pub(crate) fn take_pending(&mut self) -> VecDeque<Pending> {
+        for spec in core::mem::take(&mut DEFERRED.lock().0) {
+            self.submit(spec);
+        }
+        core::mem::take(&mut self.pending)
+    }
+
+    /// Queues notifications taken with [`take_pending`](Self::take_pending) again.
+    pub(crate) fn restore_pending(&mut self, pending: VecDeque<Pending>) {
+        for pending in pending {
+            self.enqueue(pending);
+        }
+    }
+
+    /// Restores the initial state, dropping the queue and all configuration.
+    #[cfg(feature = "mock")]
+    pub(crate) fn reset(&mut self) {
+        DEFERRED.lock().0.clear();
+        self.policy.set_sink(None);
+        *self = Self::new();
+    }
+
+    /// Live dynamic notification with the given id.
+    pub fn get(&self, id: Id) -> Option<&Notification> {
+        self.active
+            .iter()
+            .find(|a| a.id == id)
+            .map(|a| &a.notification)
+    }
+
+    /// Removes a queued notification or fades out a live dynamic notification.
+    ///
+    /// Returns `false` if the id is unknown.
+    pub fn dismiss(&mut self, id: Id) -> bool {
+        if let Some(index) = self.pending.iter().position(|p| p.id == id) {
+            self.pending.remove(index);
+            true
+        } else if let Some(index) = self.active.iter().position(|a| a.id == id) {
+            self.active.swap_remove(index).notification.dismiss();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Postpones a queued notification, or hides a live dynamic notification and shows it again
+    /// after `duration`.
+    ///
+    /// A snoozed dynamic notification is shown again with its initial text and colors. Its
+    /// callback is invoked when it is first hidden.
+    ///
+    /// Returns `false` if the id is unknown.
+    pub fn snooze(&mut self, id: Id, duration: Duration) -> bool {
+        let not_before = clock::now() + duration;
+
+        if let Some(pending) = self.pending.iter_mut().find(|p| p.id == id) {
+            pending.not_before = not_before;
+            true
+        } else if let Some(index) = self.active.iter().position(|a| a.id == id) {
+            let Active {
+                level,
+                notification,
+                template,
+                ..
+            } = self.active.swap_remove(index);
+            notification.dismiss();
+
+            self.enqueue(Pending {
+                id,
+                level,
+                spec: NotificationSpec::Dynamic(template),
+                submitted: clock::now(),
+                not_before,
+            });
+            true
+        } else {
+            false
+        }
+    }
+}
+
+unsafe impl Send for NotificationManager {}
+
+/// Notification for an entry of [`raw_enqueue`](crate::raw_enqueue), warnings are styled by the
+/// [`LevelMap`](crate::logging::LevelMap).
+fn raw_spec(level: Level, text: &'static CStr) -> Option<NotificationSpec> {
+    match level {
+        Level::Info => Some(info("").text_cstr(text).into()),
+        Level::Error => Some(error("").text_cstr(text).into()),
+        Level::Warn => level_map()
+            .action(Severity::Warn)
+            .apply(info("").text_cstr(text), None),
+    }
+}
+
+// endregion
+
+// region: Lines
+
+/// Prefixes recognized by default when writing lines to the manager.
+const LINE_PREFIXES: [(&str, Severity); 2] = [("ERR:", Severity::Error), ("WARN:", Severity::Warn)];
+
+impl NotificationManager {
+    /// Replaces the line prefixes recognized by the [`fmt::Write`] impl, defaults to `"ERR:"`
+    /// and `"WARN:"`.
+    pub fn line_prefixes(&mut self, prefixes: impl IntoIterator<Item = (&'static str, Severity)>) {
+        self.line_prefixes = prefixes.into_iter().collect();
+    }
+
+    /// Submits the text buffered by the [`fmt::Write`] impl, even without a trailing newline.
+    pub fn flush_line(&mut self) {
+        let line = core::mem::take(&mut self.line);
+        self.submit_line(line.trim_end_matches('\r'));
+    }
+
+    fn submit_line(&mut self, line: &str) {
+        let prefixes = if self.line_prefixes.is_empty() {
+            &LINE_PREFIXES[..]
+        } else {
+            &self.line_prefixes[..]
+        };
+        let recognized = prefixes
+            .iter()
+            .find_map(|(prefix, severity)| Some((line.strip_prefix(prefix)?, *severity)));
+
+        let Some((text, severity)) = recognized else {
+            if !line.trim().is_empty() {
+                self.submit(info(line));
+            }
+            return;
+        };
+        let text = text.trim_start();
+
+        if let Some(spec) = level_map().action(severity).apply(info(text), None) {
+            self.submit(spec);
+        }
+    }
+}
+
+/// Line-buffered sink, every complete line is submitted as notification.
+///
+/// Lines starting with a recognized prefix (`"ERR: …"`, `"WARN: …"`) are styled according to the
+/// [`LevelMap`](crate::logging::LevelMap) with the prefix removed, other lines are submitted as
+/// [`info`] notifications.
+impl fmt::Write for NotificationManager {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut rest = s;
+        while let Some(index) = rest.find('\n') {
+            self.line.push_str(&rest[..index]);
+            self.flush_line();
+            rest = &rest[index + 1..];
+        }
+        self.line.push_str(rest);
+        Ok(())
+    }
+}
+
+// endregion
+
+#[cfg(all(test, feature = "mock"))]
+mod tests {
+    use super::*;
+    use crate::mock::{Call, MockBackend, Simulation};
+    use notifications_sys::NotificationModuleStatus as S;
+
+    fn texts() -> Vec<String> {
+        MockBackend::calls()
+            .into_iter()
+            .filter_map(|call| match call {
+                Call::Info { text, .. } | Call::Error { text, .. } => Some(text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn history() {
+        let _sim = Simulation::start();
+        manager().keep_history(2);
+        manager().submit_all([info("a"), info("b"), info("").text_cstr(c"raw")]);
+        manager().submit(info("").text_lazy(|| String::from("lazy")).tag("net"));
+        manager().mute("muted");
+        manager().submit(info("").text_lazy(|| unreachable!()).tag("muted"));
+        assert_eq!(manager().history().count(), 0);
+
+        // recording must not consume the text
+        manager().update().unwrap();
+        assert_eq!(texts(), ["a", "b", "raw", "lazy"]);
+
+        let history: Vec<_> = manager()
+            .history()
+            .map(|(_, text)| String::from(text))
+            .collect();
+        assert_eq!(history, ["raw", "lazy"]);
+    }
+
+    #[test]
+    fn mutes() {
+        let _sim = Simulation::start();
+        manager().mute("net");
+        manager().mute("save");
+        let mut text = String::new();
+        manager().save_mutes(&mut text).unwrap();
+        assert_eq!(text, "net\nsave\n");
+
+        manager().reset();
+        manager().load_mutes("net\r\n\n  save \n");
+        let muted: Vec<_> = manager().muted().map(String::from).collect();
+        assert_eq!(muted, ["net", "save"]);
+    }
+
+    #[test]
+    fn errors_first() {
+        let _sim = Simulation::start();
+        manager().submit(info("a"));
+        manager().submit(error("b"));
+        manager().submit(info("c"));
+        manager().update().unwrap();
+        assert_eq!(texts(), ["b", "a", "c"]);
+    }
+
+    #[cfg(feature = "module-api-2")]
+    #[test]
+    fn waits_for_overlay() {
+        let _sim = Simulation::start();
+        MockBackend::set_overlay_ready(false);
+        manager().submit(info("a"));
+        manager().update().unwrap();
+        assert!(texts().is_empty());
+
+        MockBackend::set_overlay_ready(true);
+        manager().update().unwrap();
+        assert_eq!(texts(), ["a"]);
+    }
+
+    #[test]
+    fn burst_limit() {
+        let sim = Simulation::start();
+        manager().burst_limit(Some((2, Duration::from_secs(1))));
+        manager().submit_all([info("a"), info("b"), info("c")]);
+
+        sim.advance(Duration::from_millis(500)).unwrap();
+        assert_eq!(texts(), ["a", "b"]);
+        sim.advance(Duration::from_secs(1)).unwrap();
+        assert_eq!(texts(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn retries_and_continues() {
+        let _sim = Simulation::start();
+        manager().submit_all([info("a"), info("b")]);
+        MockBackend::fail_next(S::NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY);
+        assert!(manager().update().is_err());
+        manager().update().unwrap();
+        assert_eq!(texts(), ["a", "b"]);
+
+        manager().submit_all([info("c"), info("d")]);
+        MockBackend::fail_next(S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE);
+        assert!(manager().update().is_err());
+        assert_eq!(texts(), ["a", "b", "d"]);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{Notification, NotificationError, clock, idle::IdleSlot};
+use alloc::string::{String, ToString};
+use core::{fmt::Display, time::Duration};
+
+/// Dynamic notification mirroring a value, e.g. a piece of game state.
+///
+/// The text is only updated if the rendered value changed and at most every 100 ms, call
+/// [`flush`](Self::flush) to show a value held back by the throttle.
+pub struct Mirror<T: Display> {
+    value: T,
+    shown: String,
+    interval: Duration,
+    updated: Duration,
+    notification: IdleSlot,
+}
+
+impl<T: Display> Mirror<T> {
+    pub fn new(initial: T) -> Result<Self, NotificationError> {
+        let shown = initial.to_string();
+        Ok(Self {
+            notification: IdleSlot::new(&shown)?,
+            value: initial,
+            shown,
+            interval: Duration::from_millis(100),
+            updated: clock::now(),
+        })
+    }
+
+    /// Minimum time between two updates of the notification.
+    pub fn throttle(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Replaces the value, the notification is only updated if the throttle interval passed.
+    pub fn set(&mut self, value: T) -> Result<(), NotificationError> {
+        self.value = value;
+        if clock::now().saturating_sub(self.updated) < self.interval {
+            return Ok(());
+        }
+        self.flush()
+    }
+
+    /// Shows the current value if it differs from the shown one or the notification went idle,
+    /// ignoring the throttle.
+    pub fn flush(&mut self) -> Result<(), NotificationError> {
+        let text = self.value.to_string();
+        if text == self.shown && self.notification.get().is_some() {
+            return Ok(());
+        }
+
+        self.notification.text(&text)?;
+        self.shown = text;
+        self.updated = clock::now();
+        Ok(())
+    }
+
+    /// Finish the notification after `timeout` without changes, it is shown again with the next
+    /// change. Requires calling [`poll`](Self::poll) periodically.
+    pub fn idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.notification.set_timeout(timeout);
+    }
+
+    /// Finishes the notification if the [`idle_timeout`](Self::idle_timeout) passed.
+    pub fn poll(&mut self) {
+        self.notification.poll();
+    }
+
+    /// Underlying dynamic notification, `None` while idle.
+    pub fn notification(&self) -> Option<&Notification> {
+        self.notification.get()
+    }
+}
@@ -0,0 +1,627 @@
+//! Stand-in for the notification module which records calls instead of showing anything.
+//!
+//! Enabling the `mock` feature defines the `NotificationModule_*` functions in Rust and stops
+//! linking the module library, so the crate can be exercised without Aroma. When not building
+//! for the console, the coreinit functions used by the crate are defined as well, so tests and
+//! benches link on the host.
+//!
+//! [`Simulation`] runs the crate on a virtual clock, so rate limits, schedules and timeouts can
+//! be tested deterministically on the host.
+
+#![allow(non_snake_case)]
+
+use crate::{
+    NotificationError, Theme, clock, degrade, filter, logging, manager, ring, set_theme, strict,
+    summary, tick,
+};
+use alloc::{string::String, vec::Vec};
+use core::{
+    ffi::{CStr, c_char, c_void},
+    fmt::{self, Write},
+    future::Future,
+    pin::pin,
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+use notifications_sys::{
+    self as sys, NMColor, NotificationModuleHandle, NotificationModuleNotificationFinishedCallback,
+    NotificationModuleStatus as S,
+};
+use spin::{Mutex, MutexGuard};
+
+static STATE: Mutex<State> = Mutex::new(State {
+    calls: Vec::new(),
+    callbacks: Vec::new(),
+    next_handle: 1,
+    #[cfg(feature = "module-api-2")]
+    overlay_ready: true,
+    fail: None,
+});
+
+struct State {
+    calls: Vec<Call>,
+    callbacks: Vec<Callback>,
+    next_handle: NotificationModuleHandle,
+    #[cfg(feature = "module-api-2")]
+    overlay_ready: bool,
+    fail: Option<i32>,
+}
+
+/// Finish callback of a shown notification.
+struct Callback {
+    handle: NotificationModuleHandle,
+    callback: NotificationModuleNotificationFinishedCallback,
+    context: usize,
+    /// Time at which the notification fades out, `None` until a dynamic one is finished.
+    due: Option<Duration>,
+}
+
+impl Callback {
+    fn new(
+        handle: NotificationModuleHandle,
+        callback: NotificationModuleNotificationFinishedCallback,
+        context: *mut c_void,
+        due: Option<Duration>,
+    ) -> Self {
+        Self {
+            handle,
+            callback,
+            context: context as usize,
+            due,
+        }
+    }
+
+    fn invoke(self) {
+        if let Some(callback) = self.callback {
+            unsafe { callback(self.handle, self.context as *mut c_void) };
+        }
+    }
+}
+
+/// Color as passed to the module, `[r, g, b, a]`.
+pub type Rgba = [u8; 4];
+
+/// Call of a module function recorded by the [`MockBackend`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    Info {
+        text: String,
+        duration: f32,
+        text_color: Rgba,
+        background_color: Rgba,
+        keep_until_shown: bool,
+    },
+    Error {
+        text: String,
+        duration: f32,
+        shake: f32,
+        text_color: Rgba,
+        background_color: Rgba,
+        keep_until_shown: bool,
+    },
+    Dynamic {
+        handle: NotificationModuleHandle,
+        text: String,
+        text_color: Rgba,
+        background_color: Rgba,
+        keep_until_shown: bool,
+    },
+    UpdateText {
+        handle: NotificationModuleHandle,
+        text: String,
+    },
+    UpdateTextColor {
+        handle: NotificationModuleHandle,
+        color: Rgba,
+    },
+    UpdateBackgroundColor {
+        handle: NotificationModuleHandle,
+        color: Rgba,
+    },
+    Finish {
+        handle: NotificationModuleHandle,
+        delay: f32,
+        shake: f32,
+    },
+}
+
+/// Access to the state of the mocked module.
+pub struct MockBackend;
+
+impl MockBackend {
+    /// Calls recorded since the last [`reset`](Self::reset), oldest first.
+    pub fn calls() -> Vec<Call> {
+        STATE.lock().calls.clone()
+    }
+
+    /// Recorded calls as stable text, one line per call, for golden-file tests.
+    ///
+    /// ```text
+    /// info "Saved" duration=5.00 color=#ffffffff background=#000000ff
+    /// dynamic#1 "Downloading" color=#ffffffff background=#000000ff
+    /// text#1 "Downloading 50%"
+    /// finish#1 delay=0.00 shake=0.00
+    /// ```
+    ///
+    /// `keep_until_shown` is appended as ` keep` if set.
+    pub fn snapshot() -> String {
+        let mut out = String::new();
+        for call in STATE.lock().calls.iter() {
+            let _ = writeln!(out, "{call}");
+        }
+        out
+    }
+
+    /// Forgets recorded calls and pending callbacks, the overlay is ready again.
+    pub fn reset() {
+        let mut state = STATE.lock();
+        state.calls.clear();
+        state.callbacks.clear();
+        #[cfg(feature = "module-api-2")]
+        {
+            state.overlay_ready = true;
+        }
+        state.fail = None;
+    }
+
+    /// Result of `NotificationModule_IsOverlayReady`, defaults to `true`.
+    #[cfg(feature = "module-api-2")]
+    pub fn set_overlay_ready(ready: bool) {
+        STATE.lock().overlay_ready = ready;
+    }
+
+    /// The next module call returns `status` instead of succeeding.
+    pub fn fail_next(status: i32) {
+        STATE.lock().fail = Some(status);
+    }
+
+    /// Invokes the finish callbacks of all notifications shown so far, as if they faded out.
+    pub fn complete() {
+        let callbacks = core::mem::take(&mut STATE.lock().callbacks);
+        for callback in callbacks {
+            callback.invoke();
+        }
+    }
+
+    /// Invokes the finish callbacks of the notifications whose display time passed.
+    fn complete_due() {
+        let now = clock::now();
+        let due: Vec<_> = STATE
+            .lock()
+            .callbacks
+            .extract_if(.., |c| c.due.is_some_and(|due| due <= now))
+            .collect();
+        for callback in due {
+            callback.invoke();
+        }
+    }
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hex = |c: &Rgba| u32::from_be_bytes(*c);
+        let keep = |keep: &bool| if *keep { " keep" } else { "" };
+        match self {
+            Self::Info {
+                text,
+                duration,
+                text_color,
+                background_color,
+                keep_until_shown,
+            } => write!(
+                f,
+                "info {text:?} duration={duration:.2} color=#{:08x} background=#{:08x}{}",
+                hex(text_color),
+                hex(background_color),
+                keep(keep_until_shown)
+            ),
+            Self::Error {
+                text,
+                duration,
+                shake,
+                text_color,
+                background_color,
+                keep_until_shown,
+            } => write!(
+                f,
+                "error {text:?} duration={duration:.2} shake={shake:.2} color=#{:08x} background=#{:08x}{}",
+                hex(text_color),
+                hex(background_color),
+                keep(keep_until_shown)
+            ),
+            Self::Dynamic {
+                handle,
+                text,
+                text_color,
+                background_color,
+                keep_until_shown,
+            } => write!(
+                f,
+                "dynamic#{handle} {text:?} color=#{:08x} background=#{:08x}{}",
+                hex(text_color),
+                hex(background_color),
+                keep(keep_until_shown)
+            ),
+            Self::UpdateText { handle, text } => write!(f, "text#{handle} {text:?}"),
+            Self::UpdateTextColor { handle, color } => {
+                write!(f, "color#{handle} #{:08x}", hex(color))
+            }
+            Self::UpdateBackgroundColor { handle, color } => {
+                write!(f, "background#{handle} #{:08x}", hex(color))
+            }
+            Self::Finish {
+                handle,
+                delay,
+                shake,
+            } => write!(f, "finish#{handle} delay={delay:.2} shake={shake:.2}"),
+        }
+    }
+}
+
+fn rgba(color: NMColor) -> Rgba {
+    [color.r, color.g, color.b, color.a]
+}
+
+unsafe fn text(text: *const c_char) -> String {
+    unsafe { CStr::from_ptr(text) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Records `call` unless a failure was requested with [`MockBackend::fail_next`].
+fn record(state: &mut State, call: Call) -> S::Type {
+    if let Some(status) = state.fail.take() {
+        return status;
+    }
+    state.calls.push(call);
+    S::NOTIFICATION_MODULE_RESULT_SUCCESS
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn NotificationModule_InitLibrary() -> S::Type {
+    S::NOTIFICATION_MODULE_RESULT_SUCCESS
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn NotificationModule_DeInitLibrary() -> S::Type {
+    S::NOTIFICATION_MODULE_RESULT_SUCCESS
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn NotificationModule_GetVersion(
+    out: *mut sys::NotificationModuleAPIVersion,
+) -> S::Type {
+    // the minimum API level selected by the features
+    unsafe { *out = if cfg!(feature = "module-api-2") { 2 } else { 1 } };
+    S::NOTIFICATION_MODULE_RESULT_SUCCESS
+}
+
+// stubs of functions added in later API levels are gated like the generated bindings
+#[cfg(feature = "module-api-2")]
+#[unsafe(no_mangle)]
+unsafe extern "C" fn NotificationModule_IsOverlayReady(out: *mut bool) -> S::Type {
+    unsafe { *out = STATE.lock().overlay_ready };
+    S::NOTIFICATION_MODULE_RESULT_SUCCESS
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn NotificationModule_AddInfoNotificationEx(
+    text_ptr: *const c_char,
+    duration: f32,
+    text_color: NMColor,
+    background_color: NMColor,
+    callback: NotificationModuleNotificationFinishedCallback,
+    context: *mut c_void,
+    keep_until_shown: bool,
+) -> S::Type {
+    let mut state = STATE.lock();
+    let call = Call::Info {
+        text: unsafe { text(text_ptr) },
+        duration,
+        text_color: rgba(text_color),
+        background_color: rgba(background_color),
+        keep_until_shown,
+    };
+    let status = record(&mut state, call);
+    if status == S::NOTIFICATION_MODULE_RESULT_SUCCESS {
+        let due = clock::now() + Duration::from_secs_f32(duration);
+        state
+            .callbacks
+            .push(Callback::new(0, callback, context, Some(due)));
+    }
+    status
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn NotificationModule_AddErrorNotificationEx(
+    text_ptr: *const c_char,
+    duration: f32,
+    shake: f32,
+    text_color: NMColor,
+    background_color: NMColor,
+    callback: NotificationModuleNotificationFinishedCallback,
+    context: *mut c_void,
+    keep_until_shown: bool,
+) -> S::Type {
+    let mut state = STATE.lock();
+    let call = Call::Error {
+        text: unsafe { text(text_ptr) },
+        duration,
+        shake,
+        text_color: rgba(text_color),
+        background_color: rgba(background_color),
+        keep_until_shown,
+    };
+    let status = record(&mut state, call);
+    if status == S::NOTIFICATION_MODULE_RESULT_SUCCESS {
+        let due = clock::now() + Duration::from_secs_f32(duration);
+        state
+            .callbacks
+            .push(Callback::new(0, callback, context, Some(due)));
+    }
+    status
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn NotificationModule_AddDynamicNotificationEx(
+    text_ptr: *const c_char,
+    out: *mut NotificationModuleHandle,
+    text_color: NMColor,
+    background_color: NMColor,
+    callback: NotificationModuleNotificationFinishedCallback,
+    context: *mut c_void,
+    keep_until_shown: bool,
+) -> S::Type {
+    let mut state = STATE.lock();
+    let handle = state.next_handle;
+    let call = Call::Dynamic {
+        handle,
+        text: unsafe { text(text_ptr) },
+        text_color: rgba(text_color),
+        background_color: rgba(background_color),
+        keep_until_shown,
+    };
+    let status = record(&mut state, call);
+    if status == S::NOTIFICATION_MODULE_RESULT_SUCCESS {
+        state.next_handle += 1;
+        state
+            .callbacks
+            .push(Callback::new(handle, callback, context, None));
+        unsafe { *out = handle };
+    }
+    status
+}
+
+#[unsafe(no_mangle)]
+unsafe extern "C" fn NotificationModule_UpdateDynamicNotificationText(
+    handle: NotificationModuleHandle,
+    text_ptr: *const c_char,
+) -> S::Type {
+    let call = Call::UpdateText {
+        handle,
+        text: unsafe { text(text_ptr) },
+    };
+    record(&mut STATE.lock(), call)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn NotificationModule_UpdateDynamicNotificationTextColor(
+    handle: NotificationModuleHandle,
+    color: NMColor,
+) -> S::Type {
+    let call = Call::UpdateTextColor {
+        handle,
+        color: rgba(color),
+    };
+    record(&mut STATE.lock(), call)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn NotificationModule_UpdateDynamicNotificationBackgroundColor(
+    handle: NotificationModuleHandle,
+    color: NMColor,
+) -> S::Type {
+    let call = Call::UpdateBackgroundColor {
+        handle,
+        color: rgba(color),
+    };
+    record(&mut STATE.lock(), call)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn NotificationModule_FinishDynamicNotification(
+    handle: NotificationModuleHandle,
+    delay: f32,
+) -> S::Type {
+    NotificationModule_FinishDynamicNotificationWithShake(handle, delay, 0.0)
+}
+
+#[unsafe(no_mangle)]
+extern "C" fn NotificationModule_FinishDynamicNotificationWithShake(
+    handle: NotificationModuleHandle,
+    delay: f32,
+    shake: f32,
+) -> S::Type {
+    let mut state = STATE.lock();
+    let call = Call::Finish {
+        handle,
+        delay,
+        shake,
+    };
+    let status = record(&mut state, call);
+    if status == S::NOTIFICATION_MODULE_RESULT_SUCCESS {
+        let due = clock::now() + Duration::from_secs_f32(delay);
+        for callback in state.callbacks.iter_mut().filter(|c| c.handle == handle) {
+            callback.due.get_or_insert(due);
+        }
+    }
+    status
+}
+
+// region: OS
+
+// Stand-ins for the coreinit functions called by the crate, so the mock links on the host.
+// Time only advances with the `std` feature, the simulation uses the virtual clock anyway.
+
+#[cfg(not(target_arch = "powerpc"))]
+mod os {
+    use core::ffi::c_char;
+    use wut::bindings::{OSCalendarTime, OSThread, OSTime};
+
+    /// Ticks since the first call, at the rate of the console timer.
+    fn ticks() -> OSTime {
+        #[cfg(feature = "std")]
+        {
+            static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+            let elapsed = START.get_or_init(std::time::Instant::now).elapsed();
+            (elapsed.as_nanos() * crate::clock::TIMER_CLOCK / 1_000_000_000) as OSTime
+        }
+        #[cfg(not(feature = "std"))]
+        0
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn OSGetSystemTime() -> OSTime {
+        ticks()
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn OSGetTime() -> OSTime {
+        ticks()
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn OSTicksToCalendarTime(_time: OSTime, calendar: *mut OSCalendarTime) {
+        unsafe { calendar.write(OSCalendarTime::default()) };
+    }
+
+    /// Distinct per host thread, the crate only compares the addresses.
+    #[unsafe(no_mangle)]
+    extern "C" fn OSGetCurrentThread() -> *mut OSThread {
+        #[cfg(feature = "std")]
+        {
+            std::thread_local!(static THREAD: u8 = const { 0 });
+            THREAD.with(|thread| thread as *const u8 as *mut OSThread)
+        }
+        #[cfg(not(feature = "std"))]
+        core::ptr::dangling_mut()
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn OSGetThreadName(_thread: *const OSThread) -> *const c_char {
+        core::ptr::null()
+    }
+
+    #[unsafe(no_mangle)]
+    extern "C" fn OSYieldThread() {
+        core::hint::spin_loop();
+    }
+}
+
+// endregion
+
+// region: Simulation
+
+/// Runs the crate on a virtual clock starting at zero, single-threaded.
+///
+/// Every frame [`tick`]s the [`NotificationManager`](crate::NotificationManager) and invokes
+/// the finish callbacks of notifications whose duration passed. Dropping the simulation returns
+/// to the system timer.
+///
+/// ```ignore
+/// let sim = Simulation::start();
+/// manager().burst_limit(Some((2, Duration::from_secs(1))));
+/// manager().submit_all([info("a"), info("b"), info("c")]);
+/// sim.advance(Duration::from_millis(500))?;
+/// assert_eq!(MockBackend::calls().len(), 2);
+/// ```
+pub struct Simulation {
+    frame: Duration,
+    _exclusive: MutexGuard<'static, ()>,
+}
+
+/// Held by the running simulation, the crate state is global.
+static SIMULATION: Mutex<()> = Mutex::new(());
+
+impl Simulation {
+    /// Resets the [`MockBackend`] and the global state of the crate, frames are 1/60 s.
+    ///
+    /// The manager with its queue and configuration, the theme, the filters, the logging bridge,
+    /// the [`summary`](crate::summary), the notification sequence, the degraded mode and the
+    /// [`raw_enqueue`](crate::raw_enqueue) ring are reset, so every simulation starts from the
+    /// defaults.
+    ///
+    /// Waits until a simulation running on another thread, e.g. of a parallel test, is dropped.
+    pub fn start() -> Self {
+        let exclusive = SIMULATION.lock();
+        // live notifications of the manager are finished before the calls are cleared
+        manager().reset();
+        MockBackend::reset();
+        strict::ignore(set_theme(Theme::default(), false));
+        filter::set_filters(&filter::DEFAULT_FILTERS);
+        logging::reset();
+        summary::reset();
+        crate::reset();
+        degrade::recover();
+        ring::drain();
+        clock::start_at(Duration::ZERO);
+        Self {
+            frame: Duration::from_nanos(1_000_000_000 / 60),
+            _exclusive: exclusive,
+        }
+    }
+
+    /// Virtual time advanced per frame.
+    pub fn frame(mut self, frame: Duration) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Virtual time since the start.
+    pub fn now(&self) -> Duration {
+        clock::now()
+    }
+
+    /// Runs frames until `duration` passed.
+    pub fn advance(&self, duration: Duration) -> Result<(), NotificationError> {
+        let mut left = duration;
+        while !left.is_zero() {
+            let step = left.min(self.frame);
+            tick(step)?;
+            MockBackend::complete_due();
+            left -= step;
+        }
+        Ok(())
+    }
+
+    /// Polls `future` once per frame until it completes, [`Timeout`] after `timeout` of
+    /// virtual time.
+    ///
+    /// [`Timeout`]: NotificationError::Timeout
+    pub fn block_on<F: Future>(
+        &self,
+        future: F,
+        timeout: Duration,
+    ) -> Result<F::Output, NotificationError> {
+        let mut future = pin!(future);
+        let mut cx = Context::from_waker(Waker::noop());
+        let deadline = self.now() + timeout;
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return Ok(output);
+            }
+            if self.now() >= deadline {
+                return Err(NotificationError::Timeout);
+            }
+            self.advance(self.frame)?;
+        }
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        clock::untick();
+    }
+}
+
+// endregion
@@ -0,0 +1,155 @@
+use crate::{Dynamic, Error, Filters, Info, NotificationBuilder, Theme, error, info, sticky};
+use alloc::vec::Vec;
+use spin::Mutex;
+
+/// Notifier installed per OSThread, keyed by the thread pointer.
+static CONTEXTS: Mutex<Vec<(usize, Notifier)>> = Mutex::new(Vec::new());
+
+/// Creates builders with a common tag, theme and text filters, e.g. for the notifications of one library.
+///
+/// Install it with [`enter`](Self::enter) so code deeper in the call stack can use
+/// [`current`] instead of receiving it as argument.
+#[derive(Clone, Copy, Default)]
+pub struct Notifier {
+    tag: Option<&'static str>,
+    theme: Option<Theme>,
+    filters: Option<Filters>,
+}
+
+impl Notifier {
+    /// Untagged notifier using the global theme.
+    pub const fn new() -> Self {
+        Self {
+            tag: None,
+            theme: None,
+            filters: None,
+        }
+    }
+
+    pub fn tag(mut self, tag: &'static str) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// Theme used instead of the global one.
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Text preprocessing chain used instead of the global
+    /// [`filters`](crate::filter::filters).
+    pub fn filters(mut self, filters: Filters) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    #[track_caller]
+    pub fn info(&self, text: &str) -> NotificationBuilder<Info> {
+        let mut builder = info(text);
+        if let Some(theme) = &self.theme {
+            builder = builder.apply_theme(theme);
+        }
+        builder.tag = self.tag;
+        builder.filters = self.filters;
+        builder
+    }
+
+    #[track_caller]
+    pub fn error(&self, text: &str) -> NotificationBuilder<Error> {
+        let mut builder = error(text);
+        if let Some(theme) = &self.theme {
+            builder = builder.apply_theme(theme);
+        }
+        builder.tag = self.tag;
+        builder.filters = self.filters;
+        builder
+    }
+
+    #[track_caller]
+    pub fn sticky(&self, text: &str) -> NotificationBuilder<Dynamic> {
+        let mut builder = sticky(text);
+        if let Some(theme) = &self.theme {
+            builder = builder.apply_theme(theme);
+        }
+        builder.tag = self.tag;
+        builder.filters = self.filters;
+        builder
+    }
+
+    /// Installs the notifier as [`current`] for the calling thread until the guard is dropped.
+    pub fn enter(self) -> Entered {
+        Entered {
+            previous: set_current(Some(self)),
+        }
+    }
+}
+
+/// Restores the previously current notifier of the thread when dropped.
+#[must_use]
+pub struct Entered {
+    previous: Option<Notifier>,
+}
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        set_current(self.previous.take());
+    }
+}
+
+fn thread() -> usize {
+    unsafe { wut::bindings::OSGetCurrentThread() as usize }
+}
+
+/// Notifier installed for the calling thread, a default one if none is.
+pub fn current() -> Notifier {
+    let thread = thread();
+    CONTEXTS
+        .lock()
+        .iter()
+        .find(|(t, _)| *t == thread)
+        .map(|(_, notifier)| *notifier)
+        .unwrap_or_default()
+}
+
+/// Installs `notifier` for the calling thread, returns the previously installed one.
+///
+/// Threads must uninstall their notifier before exiting, since a new thread may reuse the
+/// address. Prefer [`Notifier::enter`].
+pub fn set_current(notifier: Option<Notifier>) -> Option<Notifier> {
+    let thread = thread();
+    let mut contexts = CONTEXTS.lock();
+    let index = contexts.iter().position(|(t, _)| *t == thread);
+    let previous = index.map(|i| contexts.swap_remove(i).1);
+    if let Some(notifier) = notifier {
+        contexts.push((thread, notifier));
+    }
+    previous
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme() {
+        let notifier = Notifier::new().theme(Theme {
+            prefix: "(i) ",
+            error_prefix: "(!) ",
+            thread_name: true,
+            timestamp: true,
+            ..Theme::color_blind()
+        });
+
+        let info = notifier.info("a");
+        assert_eq!(info.prefix, "(i) ");
+        assert!(info.thread_name && info.timestamp);
+
+        let error = notifier.error("a");
+        assert_eq!(error.prefix, "(!) ");
+        assert_eq!(error.background_color.g, 0x9F);
+        assert!(error.thread_name && error.timestamp);
+
+        assert!(notifier.sticky("a").timestamp);
+    }
+}
@@ -0,0 +1,81 @@
+//! Shows panics as error notifications, crashes are otherwise invisible without a PC attached.
+
+use crate::{NOTIFY, NotificationError, Operation, filter, strict, theme};
+use alloc::{borrow::Cow, ffi::CString, format};
+use core::{fmt::Display, panic::Location, time::Duration};
+use notifications_sys as sys;
+
+/// Shows a shaking error notification with the panic message and location.
+///
+/// Call it from the `#[panic_handler]` of `no_std` builds, `std` builds can use
+/// [`install_panic_hook`].
+///
+/// The panicking code may hold locks of the crate, so the notification is passed to the module
+/// directly. The theme and the filters are skipped while they are locked, the default theme and
+/// the raw text are used instead.
+pub fn show_panic(message: impl Display, location: Option<&Location<'_>>) {
+    let theme = theme::try_theme().unwrap_or_default();
+    let text = match location {
+        Some(location) => format!(
+            "{}Panic: {message} ({}:{})",
+            theme.error_prefix,
+            location.file(),
+            location.line()
+        ),
+        None => format!("{}Panic: {message}", theme.error_prefix),
+    };
+    let text = match filter::try_filters() {
+        Some(filters) => filter::apply_with(Cow::Owned(text), filters, &theme).into_owned(),
+        None => text,
+    };
+    let Ok(text) = CString::new(text.replace('\0', "")) else {
+        return;
+    };
+
+    let shake = theme.error_shake.unwrap_or(Duration::from_secs(1));
+    let color = |color: wut::gx2::color::Color| sys::NMColor {
+        r: color.r,
+        g: color.g,
+        b: color.b,
+        a: color.a,
+    };
+
+    let _r = NOTIFY.acquire();
+    let status = unsafe {
+        sys::NotificationModule_AddErrorNotificationEx(
+            text.as_ptr(),
+            theme.error_duration.as_secs_f32(),
+            shake.as_secs_f32(),
+            color(theme.error_text_color),
+            color(theme.error_background_color),
+            None,
+            core::ptr::null_mut(),
+            true,
+        )
+    };
+    strict::ignore(NotificationError::check(
+        status,
+        Operation::AddErrorNotificationEx,
+    ));
+}
+
+/// Registers a panic hook which shows the panic with [`show_panic`] before running the
+/// previous hook.
+#[cfg(feature = "std")]
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(std::boxed::Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| {
+                info.payload()
+                    .downcast_ref::<std::string::String>()
+                    .map(|s| s.as_str())
+            })
+            .unwrap_or("Box<dyn Any>");
+        show_panic(message, info.location());
+        previous(info);
+    }));
+}
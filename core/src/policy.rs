@@ -0,0 +1,316 @@
+use crate::{Theme, clock, manager::Level};
+use alloc::{string::String, vec::Vec};
+use core::{
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::Duration,
+};
+use spin::Mutex;
+
+const MINUTE: Duration = Duration::from_secs(60);
+
+/// Receives the text of notifications rerouted by a [`Rule`], e.g. to append it to a log file.
+pub type Sink = fn(Level, &str);
+
+/// Kept outside the manager, so the text shortened in degraded mode reaches it while the manager
+/// is locked.
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Sink set on the [`NotificationManager`](crate::NotificationManager).
+pub(crate) fn sink() -> Option<Sink> {
+    crate::callback(&SINK)
+}
+
+/// Rule applied by the [`NotificationManager`](crate::NotificationManager) to every submission
+/// with a matching [`tag`](crate::NotificationBuilder::tag).
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Rule {
+    /// Tag the rule applies to.
+    pub tag: String,
+    /// Submissions beyond this count within a minute are dropped.
+    pub max_per_minute: Option<u32>,
+    /// Submissions below this level are dropped.
+    pub min_level: Option<Level>,
+    /// Styling replacing the one set on the builder.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub theme: Option<Theme>,
+    /// Pass the text to the [`Sink`] instead of showing it on the overlay.
+    pub reroute: bool,
+}
+
+/// Queue state shared between several plugins using this crate, e.g. provided by a WUMS module
+/// all of them link against.
+///
+/// The [`NotificationManager`](crate::NotificationManager) asks the coordinator before queueing a
+/// submission which passed its own rules, so plugins share one rate limit and quiet mode instead
+/// of flooding the overlay independently.
+pub trait Coordinator: Send + Sync {
+    /// Counts a submission against the shared limits, returns `false` to drop it.
+    fn admit(&self, level: Level, tag: Option<&str>) -> bool;
+
+    /// Shared quiet mode, only errors are admitted while it is set.
+    fn quiet(&self) -> bool {
+        false
+    }
+}
+
+/// [`Coordinator`] over state in memory shared by all plugins, with a common limit of
+/// submissions below [`Level::Error`] per minute and a common quiet mode.
+///
+/// One plugin (or a WUMS module) exports the state, the others resolve the export and use it
+/// through [`from_ptr`](Self::from_ptr):
+///
+/// ```ignore
+/// #[unsafe(no_mangle)]
+/// pub static NOTIFICATIONS_COORDINATOR: SharedCoordinator = SharedCoordinator::new(20);
+///
+/// manager().set_coordinator(Some(&NOTIFICATIONS_COORDINATOR));
+/// ```
+///
+/// The layout is `#[repr(C)]` and versioned, so plugins built against other versions of the
+/// crate can share it. Counting is lock-free and may admit a few submissions too many when
+/// plugins submit concurrently at the end of a window.
+#[repr(C)]
+pub struct SharedCoordinator {
+    version: u32,
+    max_per_minute: AtomicU32,
+    quiet: AtomicBool,
+    /// Start of the current window in milliseconds since boot, wrapping.
+    window_start: AtomicU32,
+    count: AtomicU32,
+}
+
+impl SharedCoordinator {
+    /// Layout version, checked by [`from_ptr`](Self::from_ptr).
+    pub const VERSION: u32 = 1;
+
+    /// Admits `max_per_minute` submissions below [`Level::Error`] per minute across all plugins.
+    pub const fn new(max_per_minute: u32) -> Self {
+        Self {
+            version: Self::VERSION,
+            max_per_minute: AtomicU32::new(max_per_minute),
+            quiet: AtomicBool::new(false),
+            window_start: AtomicU32::new(0),
+            count: AtomicU32::new(0),
+        }
+    }
+
+    /// State exported by another plugin, `None` if `ptr` is null or of another layout version.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or point to a [`SharedCoordinator`] which is valid for the rest of the
+    /// program, e.g. the export of a module which is never unloaded.
+    pub unsafe fn from_ptr(ptr: *const Self) -> Option<&'static Self> {
+        let state = unsafe { ptr.as_ref()? };
+        (state.version == Self::VERSION).then_some(state)
+    }
+
+    pub fn set_max_per_minute(&self, max: u32) {
+        self.max_per_minute.store(max, Ordering::Relaxed);
+    }
+
+    /// Sets the quiet mode of all plugins, e.g. from the settings of one of them.
+    pub fn set_quiet(&self, quiet: bool) {
+        self.quiet.store(quiet, Ordering::Relaxed);
+    }
+
+    fn admit_at(&self, level: Level, now: u32) -> bool {
+        if level >= Level::Error {
+            return true;
+        }
+
+        let start = self.window_start.load(Ordering::Relaxed);
+        if now.wrapping_sub(start) >= MINUTE.as_millis() as u32
+            && self
+                .window_start
+                .compare_exchange(start, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            self.count.store(0, Ordering::Relaxed);
+        }
+
+        self.count.fetch_add(1, Ordering::Relaxed) < self.max_per_minute.load(Ordering::Relaxed)
+    }
+}
+
+impl Coordinator for SharedCoordinator {
+    fn admit(&self, level: Level, _tag: Option<&str>) -> bool {
+        // the system timer is shared, tick mode is local to each plugin
+        self.admit_at(level, clock::real().as_millis() as u32)
+    }
+
+    fn quiet(&self) -> bool {
+        self.quiet.load(Ordering::Relaxed)
+    }
+}
+
+/// Outcome of evaluating the rules for a submission.
+pub(crate) enum Verdict {
+    Show(Option<Theme>),
+    Reroute,
+    Drop,
+}
+
+/// Rules table with the rate counters of each rule.
+#[derive(Default)]
+pub(crate) struct Policy {
+    rules: Vec<(Rule, Window)>,
+    muted: Vec<String>,
+    quiet: bool,
+    coordinator: Option<&'static dyn Coordinator>,
+}
+
+#[derive(Default)]
+struct Window {
+    start: Duration,
+    count: u32,
+}
+
+impl Policy {
+    pub(crate) const fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            muted: Vec::new(),
+            quiet: false,
+            coordinator: None,
+        }
+    }
+
+    pub(crate) fn set_rules(&mut self, rules: impl IntoIterator<Item = Rule>) {
+        self.rules = rules
+            .into_iter()
+            .map(|rule| (rule, Window::default()))
+            .collect();
+    }
+
+    pub(crate) fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.rules.iter().map(|(rule, _)| rule)
+    }
+
+    pub(crate) fn mute(&mut self, tag: &str) {
+        if !self.is_muted(tag) {
+            self.muted.push(String::from(tag));
+        }
+    }
+
+    pub(crate) fn unmute(&mut self, tag: &str) {
+        self.muted.retain(|t| t != tag);
+    }
+
+    pub(crate) fn is_muted(&self, tag: &str) -> bool {
+        self.muted.iter().any(|t| t == tag)
+    }
+
+    pub(crate) fn muted(&self) -> impl Iterator<Item = &str> {
+        self.muted.iter().map(String::as_str)
+    }
+
+    pub(crate) fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    pub(crate) fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    pub(crate) fn set_sink(&mut self, sink: Option<Sink>) {
+        *SINK.lock() = sink;
+    }
+
+    pub(crate) fn sink(&self) -> Option<Sink> {
+        sink()
+    }
+
+    pub(crate) fn set_coordinator(&mut self, coordinator: Option<&'static dyn Coordinator>) {
+        self.coordinator = coordinator;
+    }
+
+    /// Applies the quiet mode and asks the [`Coordinator`] whether a submission passing the rules
+    /// may be queued.
+    pub(crate) fn coordinate(&self, tag: Option<&str>, level: Level) -> bool {
+        if self.quiet && level < Level::Error {
+            return false;
+        }
+        let Some(coordinator) = self.coordinator else {
+            return true;
+        };
+        if coordinator.quiet() && level < Level::Error {
+            return false;
+        }
+        coordinator.admit(level, tag)
+    }
+
+    /// Evaluates the first rule matching `tag`, untagged submissions are always shown.
+    ///
+    /// Muted tags are dropped before any rule is evaluated.
+    pub(crate) fn evaluate(&mut self, tag: Option<&str>, level: Level) -> Verdict {
+        let Some(tag) = tag else {
+            return Verdict::Show(None);
+        };
+        if self.is_muted(tag) {
+            return Verdict::Drop;
+        }
+        let Some((rule, window)) = self.rules.iter_mut().find(|(rule, _)| rule.tag == tag) else {
+            return Verdict::Show(None);
+        };
+
+        if rule.min_level.is_some_and(|min| level < min) {
+            return Verdict::Drop;
+        }
+
+        if let Some(max) = rule.max_per_minute {
+            let now = clock::now();
+            if now.saturating_sub(window.start) >= MINUTE {
+                window.start = now;
+                window.count = 0;
+            }
+            if window.count >= max {
+                return Verdict::Drop;
+            }
+            window.count += 1;
+        }
+
+        if rule.reroute {
+            Verdict::Reroute
+        } else {
+            Verdict::Show(rule.theme)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_limit() {
+        let shared = SharedCoordinator::new(2);
+        let admitted = [0, 10, 20, 30]
+            .map(|now| shared.admit_at(Level::Info, now))
+            .map(u8::from);
+        assert_eq!(admitted, [1, 1, 0, 0]);
+        assert!(shared.admit_at(Level::Error, 40));
+        assert!(shared.admit_at(Level::Warn, 60_000));
+
+        // the clock wraps after ~49 days
+        let shared = SharedCoordinator::new(1);
+        assert!(shared.admit_at(Level::Info, u32::MAX - 10));
+        assert!(!shared.admit_at(Level::Info, 5));
+        assert!(shared.admit_at(Level::Info, 60_000));
+    }
+
+    #[test]
+    fn layout_version() {
+        static SHARED: SharedCoordinator = SharedCoordinator::new(1);
+        static OTHER: SharedCoordinator = SharedCoordinator {
+            version: SharedCoordinator::VERSION + 1,
+            ..SharedCoordinator::new(1)
+        };
+
+        assert!(unsafe { SharedCoordinator::from_ptr(core::ptr::null()) }.is_none());
+        assert!(unsafe { SharedCoordinator::from_ptr(&OTHER) }.is_none());
+        assert!(unsafe { SharedCoordinator::from_ptr(&SHARED) }.is_some());
+    }
+}
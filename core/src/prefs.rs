@@ -0,0 +1,175 @@
+use crate::{NotificationError, Theme, manager, set_theme};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// Theme preset picked by the end user, see [`Preferences`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThemeChoice {
+    #[default]
+    Default,
+    /// [`Theme::color_blind`].
+    ColorBlind,
+}
+
+impl ThemeChoice {
+    pub fn theme(self) -> Theme {
+        match self {
+            Self::Default => Theme::default(),
+            Self::ColorBlind => Theme::color_blind(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::ColorBlind => "color-blind",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        [Self::Default, Self::ColorBlind]
+            .into_iter()
+            .find(|choice| choice.name() == name)
+    }
+}
+
+/// Notification settings of the end user which survive relaunches of the plugin.
+///
+/// The plugin owns the value, e.g. edits it from its settings page, and calls
+/// [`apply`](Self::apply) and [`save_file`](Self::save_file) on every change:
+///
+/// ```ignore
+/// const PATH: &str = "fs:/vol/external01/wiiu/my_plugin/notifications.txt";
+///
+/// let prefs = Preferences::load_file(PATH).unwrap_or_default();
+/// prefs.apply()?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preferences {
+    /// See [`NotificationManager::set_quiet`](crate::NotificationManager::set_quiet).
+    pub quiet: bool,
+    /// See [`NotificationManager::mute`](crate::NotificationManager::mute).
+    pub muted: Vec<String>,
+    pub theme: ThemeChoice,
+}
+
+impl Preferences {
+    /// Sets the quiet mode, replaces the muted tags and the active theme.
+    pub fn apply(&self) -> Result<(), NotificationError> {
+        {
+            let mut manager = manager();
+            manager.set_quiet(self.quiet);
+            let muted: Vec<String> = manager.muted().map(String::from).collect();
+            for tag in &muted {
+                manager.unmute(tag);
+            }
+            for tag in &self.muted {
+                manager.mute(tag);
+            }
+        }
+        set_theme(self.theme.theme(), true)
+    }
+
+    /// Writes one `key=value` line per setting and muted tag.
+    pub fn save(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        writeln!(out, "quiet={}", self.quiet)?;
+        writeln!(out, "theme={}", self.theme.name())?;
+        for tag in &self.muted {
+            writeln!(out, "mute={tag}")?;
+        }
+        Ok(())
+    }
+
+    /// Reads the text written by [`save`](Self::save).
+    ///
+    /// Unknown keys and values are skipped, so files written by other versions of the crate
+    /// still load.
+    pub fn load(text: &str) -> Self {
+        let mut prefs = Self::default();
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "quiet" => prefs.quiet = value == "true",
+                "theme" => prefs.theme = ThemeChoice::parse(value).unwrap_or_default(),
+                "mute" if !value.is_empty() && !prefs.muted.iter().any(|t| t == value) => {
+                    prefs.muted.push(String::from(value));
+                }
+                _ => {}
+            }
+        }
+        prefs
+    }
+
+    /// Reads the preferences from a file, e.g. on the SD card. A missing file yields the
+    /// defaults.
+    #[cfg(feature = "std")]
+    pub fn load_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::load(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes the preferences to a file, e.g. on the SD card.
+    #[cfg(feature = "std")]
+    pub fn save_file(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let mut text = String::new();
+        self.save(&mut text).map_err(std::io::Error::other)?;
+        std::fs::write(path, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let prefs = Preferences {
+            quiet: true,
+            muted: ["net", "save"].map(String::from).to_vec(),
+            theme: ThemeChoice::ColorBlind,
+        };
+        let mut text = String::new();
+        prefs.save(&mut text).unwrap();
+        assert_eq!(text, "quiet=true\ntheme=color-blind\nmute=net\nmute=save\n");
+        assert_eq!(Preferences::load(&text), prefs);
+
+        let prefs = Preferences::load("quiet = false\r\ntheme=neon\nmute=\nvolume=3\nmute=net\n");
+        assert_eq!(
+            prefs,
+            Preferences {
+                muted: ["net"].map(String::from).to_vec(),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn apply() {
+        use crate::{
+            error, info,
+            mock::{Call, MockBackend, Simulation},
+        };
+
+        let _sim = Simulation::start();
+        manager().mute("old");
+        Preferences::load("quiet=true\nmute=net\n").apply().unwrap();
+        assert!(manager().is_quiet());
+        assert_eq!(manager().muted().collect::<Vec<_>>(), ["net"]);
+
+        manager().submit(info("hidden"));
+        manager().submit(error("shown"));
+        manager().update().unwrap();
+        let shown = MockBackend::calls()
+            .into_iter()
+            .filter(|call| matches!(call, Call::Info { .. } | Call::Error { .. }))
+            .count();
+        assert_eq!(shown, 1);
+    }
+}
@@ -0,0 +1,602 @@
+use crate::{Notification, NotificationError, Theme, clock, dynamic, theme};
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+use wut::gx2::color::Color;
+
+// region: CancelToken
+
+/// Shared flag signalling that an operation should be cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation, visible to all clones of this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+// endregion
+
+// region: Formatter
+
+/// Renders the numbers shown by a [`Progress`].
+pub trait Formatter: Sync {
+    /// Appends a fraction in `0.0..=1.0` as percentage.
+    fn percent(&self, out: &mut String, fraction: f32);
+
+    /// Appends a byte count.
+    fn bytes(&self, out: &mut String, bytes: u64);
+
+    /// Appends the estimated remaining time.
+    fn eta(&self, out: &mut String, eta: Duration);
+}
+
+/// Locale dependent number formatting.
+///
+/// The [`Default`] uses a decimal point and no digit grouping.
+#[derive(Debug, Clone, Copy)]
+pub struct Locale {
+    /// Separator between integer and fractional digits.
+    pub decimal: char,
+    /// Separator between groups of thousands, `None` disables grouping.
+    pub group: Option<char>,
+    /// Placed between a number and its unit, e.g. a narrow no-break space.
+    pub unit_space: &'static str,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::decimal_point()
+    }
+}
+
+impl Locale {
+    /// Decimal point without grouping, e.g. `1234.5 MiB`.
+    pub const fn decimal_point() -> Self {
+        Self {
+            decimal: '.',
+            group: None,
+            unit_space: " ",
+        }
+    }
+
+    /// Decimal comma and dot grouping, e.g. `1.234,5 MiB`.
+    pub const fn decimal_comma() -> Self {
+        Self {
+            decimal: ',',
+            group: Some('.'),
+            unit_space: "\u{202F}",
+        }
+    }
+
+    fn number(&self, out: &mut String, value: f64, decimals: usize) {
+        let mut digits = String::new();
+        let _ = write!(digits, "{value:.decimals$}");
+
+        let (int, frac) = match digits.split_once('.') {
+            Some((int, frac)) => (int, Some(frac)),
+            None => (digits.as_str(), None),
+        };
+
+        for (i, c) in int.chars().enumerate() {
+            if let Some(group) = self.group {
+                if i > 0 && (int.len() - i) % 3 == 0 {
+                    out.push(group);
+                }
+            }
+            out.push(c);
+        }
+
+        if let Some(frac) = frac {
+            out.push(self.decimal);
+            out.push_str(frac);
+        }
+    }
+}
+
+impl Formatter for Locale {
+    fn percent(&self, out: &mut String, fraction: f32) {
+        self.number(out, (fraction.clamp(0.0, 1.0) * 100.0) as f64, 0);
+        out.push_str(self.unit_space);
+        out.push('%');
+    }
+
+    fn bytes(&self, out: &mut String, bytes: u64) {
+        const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+
+        self.number(out, value, if unit == 0 { 0 } else { 1 });
+        out.push_str(self.unit_space);
+        out.push_str(UNITS[unit]);
+    }
+
+    fn eta(&self, out: &mut String, eta: Duration) {
+        let secs = eta.as_secs();
+        if secs >= 60 {
+            let _ = write!(out, "{}m{}{:02}s", secs / 60, self.unit_space, secs % 60);
+        } else {
+            let _ = write!(out, "{secs}s");
+        }
+    }
+}
+
+// endregion
+
+// region: Progress
+
+static DEFAULT_LOCALE: Locale = Locale::decimal_point();
+
+struct Subtask {
+    index: usize,
+    label: String,
+    fraction: f32,
+}
+
+/// Dynamic notification showing the progress of an operation.
+///
+/// Renders as `"{label} 42% (1.5 MiB / 3.0 MiB, 12s left)"`, or as
+/// `"{label} (2/5): {subtask} 60%"` while a subtask is active.
+pub struct Progress {
+    label: String,
+    fraction: f32,
+    weights: Vec<f32>,
+    subtask: Option<Subtask>,
+    bytes: Option<(u64, u64)>,
+    started: Duration,
+    formatter: &'static dyn Formatter,
+    token: Option<CancelToken>,
+    cancelled_theme: Option<Theme>,
+    notification: Option<Notification>,
+}
+
+impl Progress {
+    /// Shows a new progress notification at 0%.
+    pub fn new(label: &str) -> Result<Self, NotificationError> {
+        let mut progress = Self::hidden(label);
+        progress.notification = Some(dynamic(label).show()?);
+        progress.update()?;
+
+        Ok(progress)
+    }
+
+    /// Tracks progress without showing a notification, e.g. as part of a [`ProgressGroup`].
+    pub fn hidden(label: &str) -> Self {
+        Self {
+            label: String::from(label),
+            fraction: 0.0,
+            weights: Vec::new(),
+            subtask: None,
+            bytes: None,
+            started: clock::now(),
+            formatter: &DEFAULT_LOCALE,
+            token: None,
+            cancelled_theme: None,
+            notification: None,
+        }
+    }
+
+    /// Replaces the [`Formatter`] used for all numbers.
+    pub fn formatter(
+        &mut self,
+        formatter: &'static dyn Formatter,
+    ) -> Result<(), NotificationError> {
+        self.formatter = formatter;
+        self.update()
+    }
+
+    /// Observes `token`, once it is cancelled the notification shows `"{label} Cancelling…"` in
+    /// the [`cancelled_theme`](Self::cancelled_theme) and fades out.
+    ///
+    /// The token is checked on every update, [`poll`](Self::poll) checks it while the progress
+    /// doesn't change, e.g. when another thread cancels it.
+    pub fn cancel_token(&mut self, token: CancelToken) -> Result<(), NotificationError> {
+        self.token = Some(token);
+        self.update()
+    }
+
+    /// Token shared with the producer, created on first use.
+    pub fn token(&mut self) -> CancelToken {
+        self.token.get_or_insert_default().clone()
+    }
+
+    /// Cancels the observed token and updates the notification.
+    pub fn cancel(&mut self) -> Result<(), NotificationError> {
+        self.token().cancel();
+        self.update()
+    }
+
+    /// Whether the observed token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.as_ref().is_some_and(|t| t.is_cancelled())
+    }
+
+    /// Colors and fade out delay once cancelled, defaults to the active theme on a gray
+    /// background.
+    pub fn cancelled_theme(&mut self, theme: Theme) {
+        self.cancelled_theme = Some(theme);
+    }
+
+    /// Finishes the notification once the observed token was cancelled, returns whether it was.
+    pub fn poll(&mut self) -> Result<bool, NotificationError> {
+        if !self.is_cancelled() {
+            return Ok(false);
+        }
+        self.finish_cancelled().map(|()| true)
+    }
+
+    /// Sets the completed fraction in `0.0..=1.0`, a non-finite `fraction` is ignored.
+    pub fn set(&mut self, fraction: f32) -> Result<(), NotificationError> {
+        if fraction.is_finite() {
+            self.fraction = fraction.clamp(0.0, 1.0);
+        }
+        self.update()
+    }
+
+    /// Sets the completed fraction from transferred bytes.
+    pub fn set_bytes(&mut self, done: u64, total: u64) -> Result<(), NotificationError> {
+        self.bytes = Some((done, total));
+        match total {
+            0 => self.set(1.0),
+            _ => self.set(done as f32 / total as f32),
+        }
+    }
+
+    /// Splits the operation into subtasks with the given relative weights.
+    pub fn subtasks(&mut self, weights: impl IntoIterator<Item = f32>) {
+        self.weights = weights.into_iter().map(|w| w.max(0.0)).collect();
+        self.subtask = None;
+    }
+
+    /// Starts the subtask at `index` with a sub-fraction of 0%.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not covered by [`subtasks`](Self::subtasks).
+    pub fn subtask(&mut self, index: usize, label: &str) -> Result<(), NotificationError> {
+        assert!(index < self.weights.len(), "subtask index out of bounds");
+
+        self.subtask = Some(Subtask {
+            index,
+            label: String::from(label),
+            fraction: 0.0,
+        });
+        self.set_subtask(0.0)
+    }
+
+    /// Sets the completed fraction of the active subtask in `0.0..=1.0` and derives the overall
+    /// fraction from the subtask weights.
+    ///
+    /// Does nothing if no subtask is active or `fraction` is not finite.
+    pub fn set_subtask(&mut self, fraction: f32) -> Result<(), NotificationError> {
+        let Some(subtask) = &mut self.subtask else {
+            return Ok(());
+        };
+        if !fraction.is_finite() {
+            return Ok(());
+        }
+        subtask.fraction = fraction.clamp(0.0, 1.0);
+
+        let total: f32 = self.weights.iter().sum();
+        if total > 0.0 {
+            let done: f32 = self.weights[..subtask.index].iter().sum();
+            let current = self.weights[subtask.index] * subtask.fraction;
+            self.fraction = ((done + current) / total).clamp(0.0, 1.0);
+        }
+
+        self.update()
+    }
+
+    /// Completed fraction in `0.0..=1.0`.
+    pub fn fraction(&self) -> f32 {
+        self.fraction
+    }
+
+    /// Whether the fraction reached 100%.
+    pub fn is_done(&self) -> bool {
+        self.fraction >= 1.0
+    }
+
+    /// Underlying dynamic notification, `None` if [`hidden`](Self::hidden).
+    pub fn notification(&self) -> Option<&Notification> {
+        self.notification.as_ref()
+    }
+
+    /// Estimated remaining time based on the progress so far, `None` if it is out of range.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.fraction <= 0.0 || self.fraction >= 1.0 {
+            return None;
+        }
+
+        let elapsed = clock::now().saturating_sub(self.started).as_secs_f32();
+        Duration::try_from_secs_f32(elapsed * (1.0 - self.fraction) / self.fraction).ok()
+    }
+
+    fn update(&mut self) -> Result<(), NotificationError> {
+        if self.is_cancelled() {
+            return self.finish_cancelled();
+        }
+        let Some(notification) = &self.notification else {
+            return Ok(());
+        };
+
+        notification.text(&self.render())
+    }
+
+    /// Shows `"{label} Cancelling…"` in the cancelled theme and fades the notification out.
+    fn finish_cancelled(&mut self) -> Result<(), NotificationError> {
+        let Some(notification) = self.notification.take() else {
+            return Ok(());
+        };
+
+        let theme = self.cancelled_theme.unwrap_or_else(|| Theme {
+            background_color: Color {
+                r: 0x60,
+                g: 0x60,
+                b: 0x60,
+                a: 0xC0,
+            },
+            ..theme()
+        });
+        let mut text = self.label.clone();
+        text.push_str(" Cancelling\u{2026}");
+
+        let result = notification
+            .text_color(theme.text_color)
+            .and_then(|()| notification.bg_color(theme.background_color))
+            .and_then(|()| notification.text(&text));
+        // finished even if restyling failed, so it doesn't keep showing the progress
+        let finished = notification.finish_with(theme.duration, None);
+        result.and(finished.map(drop))
+    }
+
+    fn render(&self) -> String {
+        let mut text = self.label.clone();
+        match &self.subtask {
+            Some(subtask) => {
+                let _ = write!(
+                    text,
+                    " ({}/{}): {} ",
+                    subtask.index + 1,
+                    self.weights.len(),
+                    subtask.label
+                );
+                self.formatter.percent(&mut text, subtask.fraction);
+            }
+            None => {
+                text.push(' ');
+                self.formatter.percent(&mut text, self.fraction);
+            }
+        }
+
+        let eta = self.eta();
+        if self.bytes.is_some() || eta.is_some() {
+            text.push_str(" (");
+            if let Some((done, total)) = self.bytes {
+                self.formatter.bytes(&mut text, done);
+                text.push_str(" / ");
+                self.formatter.bytes(&mut text, total);
+            }
+            if let Some(eta) = eta {
+                if self.bytes.is_some() {
+                    text.push_str(", ");
+                }
+                self.formatter.eta(&mut text, eta);
+                text.push_str(" left");
+            }
+            text.push(')');
+        }
+
+        text
+    }
+}
+
+// endregion
+
+// region: ProgressGroup
+
+/// Single notification summarizing several [`Progress`] trackers.
+///
+/// Renders as `"{label} 3/7, 42%"`, where `3/7` counts the finished children.
+pub struct ProgressGroup {
+    label: String,
+    children: Vec<Progress>,
+    formatter: &'static dyn Formatter,
+    notification: Notification,
+}
+
+impl ProgressGroup {
+    /// Shows a new group notification without any children.
+    pub fn new(label: &str) -> Result<Self, NotificationError> {
+        let group = Self {
+            label: String::from(label),
+            children: Vec::new(),
+            formatter: &DEFAULT_LOCALE,
+            notification: dynamic(label).show()?,
+        };
+        group.update()?;
+
+        Ok(group)
+    }
+
+    /// Replaces the [`Formatter`] used for all numbers.
+    pub fn formatter(
+        &mut self,
+        formatter: &'static dyn Formatter,
+    ) -> Result<(), NotificationError> {
+        self.formatter = formatter;
+        self.update()
+    }
+
+    /// Adds a child tracker and returns its index.
+    pub fn add(&mut self, label: &str) -> Result<usize, NotificationError> {
+        self.children.push(Progress::hidden(label));
+        self.update()?;
+
+        Ok(self.children.len() - 1)
+    }
+
+    /// Sets the completed fraction of a child in `0.0..=1.0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, fraction: f32) -> Result<(), NotificationError> {
+        self.children[index].set(fraction)?;
+        self.update()
+    }
+
+    /// Sets the completed fraction of a child from transferred bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_bytes(
+        &mut self,
+        index: usize,
+        done: u64,
+        total: u64,
+    ) -> Result<(), NotificationError> {
+        self.children[index].set_bytes(done, total)?;
+        self.update()
+    }
+
+    /// Child trackers in the order they were added.
+    pub fn children(&self) -> &[Progress] {
+        &self.children
+    }
+
+    /// Average fraction of all children.
+    pub fn fraction(&self) -> f32 {
+        if self.children.is_empty() {
+            return 0.0;
+        }
+
+        self.children.iter().map(|c| c.fraction).sum::<f32>() / self.children.len() as f32
+    }
+
+    /// Underlying dynamic notification.
+    pub fn notification(&self) -> &Notification {
+        &self.notification
+    }
+
+    fn update(&self) -> Result<(), NotificationError> {
+        self.notification.text(&self.render())
+    }
+
+    fn render(&self) -> String {
+        let done = self.children.iter().filter(|c| c.is_done()).count();
+
+        let mut text = self.label.clone();
+        let _ = write!(text, " {done}/{}, ", self.children.len());
+        self.formatter.percent(&mut text, self.fraction());
+
+        text
+    }
+}
+
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(locale: Locale, value: f64, decimals: usize) -> String {
+        let mut out = String::new();
+        locale.number(&mut out, value, decimals);
+        out
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number(Locale::decimal_point(), 1234.56, 1), "1234.6");
+        assert_eq!(
+            number(Locale::decimal_comma(), 1234567.89, 2),
+            "1.234.567,89"
+        );
+        assert_eq!(number(Locale::decimal_comma(), 999.0, 0), "999");
+        assert_eq!(number(Locale::decimal_comma(), 0.5, 1), "0,5");
+    }
+
+    #[test]
+    fn units() {
+        let locale = Locale::decimal_point();
+        let mut out = String::new();
+        locale.bytes(&mut out, 512);
+        out.push('|');
+        locale.bytes(&mut out, 1536);
+        out.push('|');
+        locale.percent(&mut out, 1.5);
+        out.push('|');
+        locale.eta(&mut out, Duration::from_secs(75));
+        assert_eq!(out, "512 B|1.5 KiB|100 %|1m 15s");
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn eta_out_of_range() {
+        let sim = crate::mock::Simulation::start();
+        let mut progress = Progress::hidden("Copying");
+        sim.advance(Duration::from_secs(2)).unwrap();
+
+        progress.set(0.5).unwrap();
+        progress.set(f32::NAN).unwrap();
+        assert_eq!(progress.fraction(), 0.5);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(2)));
+
+        progress.set_bytes(1, u64::MAX).unwrap();
+        assert!(progress.fraction() > 0.0);
+        assert_eq!(progress.eta(), None);
+        assert!(progress.render().starts_with("Copying 0"));
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn cancelled() {
+        use crate::mock::{Call, MockBackend};
+
+        let _sim = crate::mock::Simulation::start();
+        let mut progress = Progress::new("Copying").unwrap();
+        let token = progress.token();
+        assert!(!progress.poll().unwrap());
+
+        token.cancel();
+        assert!(progress.poll().unwrap());
+        assert!(progress.notification().is_none());
+        // nothing left to update
+        progress.set(0.5).unwrap();
+
+        let calls = MockBackend::calls();
+        let n = calls.len();
+        assert!(matches!(
+            &calls[n - 4..],
+            [
+                Call::UpdateTextColor { .. },
+                Call::UpdateBackgroundColor {
+                    color: [0x60, 0x60, 0x60, 0xC0],
+                    ..
+                },
+                Call::UpdateText { text, .. },
+                Call::Finish { .. },
+            ] if text == "Copying Cancelling\u{2026}"
+        ));
+    }
+}
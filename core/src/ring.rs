@@ -0,0 +1,85 @@
+use crate::Level;
+use alloc::vec::Vec;
+use core::{
+    ffi::{CStr, c_char},
+    ptr,
+    sync::atomic::{AtomicPtr, AtomicU8, AtomicU32, Ordering},
+};
+
+const CAPACITY: usize = 32;
+
+const EMPTY: u8 = 0;
+const WRITING: u8 = 1;
+const FULL: u8 = 2;
+
+struct Slot {
+    state: AtomicU8,
+    level: AtomicU8,
+    sequence: AtomicU32,
+    text: AtomicPtr<c_char>,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const SLOT: Slot = Slot {
+    state: AtomicU8::new(EMPTY),
+    level: AtomicU8::new(0),
+    sequence: AtomicU32::new(0),
+    text: AtomicPtr::new(ptr::null_mut()),
+};
+
+static SLOTS: [Slot; CAPACITY] = [SLOT; CAPACITY];
+static HEAD: AtomicU32 = AtomicU32::new(0);
+
+/// Queues a notification without allocating or blocking, e.g. from GX2 or audio callbacks.
+///
+/// The notification is shown by the next [`update`](crate::NotificationManager::update) of the
+/// manager. Up to 32 notifications can be pending, returns `false` if the queue is full.
+pub fn raw_enqueue(text: &'static CStr, level: Level) -> bool {
+    let sequence = HEAD.fetch_add(1, Ordering::Relaxed);
+    let start = sequence as usize % CAPACITY;
+
+    for i in 0..CAPACITY {
+        let slot = &SLOTS[(start + i) % CAPACITY];
+        if slot
+            .state
+            .compare_exchange(EMPTY, WRITING, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            continue;
+        }
+
+        slot.level.store(level as u8, Ordering::Relaxed);
+        slot.sequence.store(sequence, Ordering::Relaxed);
+        slot.text
+            .store(text.as_ptr() as *mut c_char, Ordering::Relaxed);
+        slot.state.store(FULL, Ordering::Release);
+        return true;
+    }
+
+    false
+}
+
+/// Takes all queued notifications in the order they were queued.
+pub(crate) fn drain() -> Vec<(Level, &'static CStr)> {
+    let mut entries = Vec::new();
+    for slot in &SLOTS {
+        if slot.state.load(Ordering::Acquire) != FULL {
+            continue;
+        }
+
+        let level = match slot.level.load(Ordering::Relaxed) {
+            l if l == Level::Error as u8 => Level::Error,
+            l if l == Level::Warn as u8 => Level::Warn,
+            _ => Level::Info,
+        };
+        let text = unsafe { CStr::from_ptr(slot.text.load(Ordering::Relaxed)) };
+        entries.push((slot.sequence.load(Ordering::Relaxed), level, text));
+        slot.state.store(EMPTY, Ordering::Release);
+    }
+
+    entries.sort_unstable_by_key(|(sequence, ..)| *sequence);
+    entries
+        .into_iter()
+        .map(|(_, level, text)| (level, text))
+        .collect()
+}
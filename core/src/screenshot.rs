@@ -0,0 +1,31 @@
+//! Screenshots attached to error notifications, so bug reports show what happened.
+//!
+//! The crate doesn't talk to a screenshot module itself. Install a [`Hook`] which triggers the
+//! screenshot (e.g. through the Aroma screenshot plugin, if present) and returns the file name.
+
+use crate::{Error, NotificationBuilder};
+use spin::Mutex;
+
+/// Takes a screenshot and returns its file name, `None` if none could be taken.
+pub type Hook = fn() -> Option<alloc::string::String>;
+
+static HOOK: Mutex<Option<Hook>> = Mutex::new(None);
+
+/// Sets the hook called for every error notification, `None` disables screenshots.
+pub fn set_hook(hook: Option<Hook>) {
+    *HOOK.lock() = hook;
+}
+
+/// Takes a screenshot and adds its file name as `screenshot` field.
+pub(crate) fn attach(builder: &mut NotificationBuilder<Error>) {
+    // already attached by a failed attempt
+    if builder.fields.iter().any(|(key, _)| *key == "screenshot") {
+        return;
+    }
+    let hook = *HOOK.lock();
+    if let Some(name) = hook.and_then(|hook| hook()) {
+        builder.fields.push(("screenshot", name));
+        // the text encoded ahead of time misses the field
+        builder.encoded = None;
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{NOTIFY, NotificationError, Version, info, overlay_status, version};
+use core::time::Duration;
+
+/// Result of [`self_test`], one entry per step.
+#[derive(Debug, Clone)]
+pub struct SelfTest {
+    /// API version of the installed module, fails if the module is missing.
+    pub version: Result<Version, NotificationError>,
+    /// The overlay can show notifications right now, `None` if skipped because the module
+    /// API level 2 is disabled and the module can't report it.
+    pub overlay_ready: Option<Result<bool, NotificationError>>,
+    /// Showing the test notification.
+    pub notification: Result<(), NotificationError>,
+}
+
+impl SelfTest {
+    /// Every step succeeded and the overlay is ready, skipped steps count as passed.
+    pub fn passed(&self) -> bool {
+        self.version.is_ok()
+            && matches!(self.overlay_ready, None | Some(Ok(true)))
+            && self.notification.is_ok()
+    }
+}
+
+/// Checks the module and shows a short test notification, e.g. for a "test notifications"
+/// button in the settings of a plugin.
+///
+/// Every step runs even if a previous one failed, so the report is complete.
+pub fn self_test() -> SelfTest {
+    let _r = NOTIFY.acquire();
+
+    SelfTest {
+        version: version::query(),
+        overlay_ready: cfg!(feature = "module-api-2").then(overlay_status),
+        notification: info("Notifications are working")
+            .duration(Duration::from_secs(2))
+            .show()
+            .map(|_| ()),
+    }
+}
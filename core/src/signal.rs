@@ -0,0 +1,51 @@
+use alloc::sync::Arc;
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{Context, Poll, Waker},
+};
+use spin::Mutex;
+
+/// One-shot flag set from the finish callback of a notification.
+#[derive(Default)]
+pub(crate) struct Signal {
+    set: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Signal {
+    pub(crate) fn set(&self) {
+        self.set.store(true, Ordering::Release);
+        if let Some(waker) = self.waker.lock().take() {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn is_set(&self) -> bool {
+        self.set.load(Ordering::Acquire)
+    }
+}
+
+/// Future returned by [`Notification::finished`](crate::Notification::finished), completes once
+/// the overlay removed the notification.
+pub struct WaitFinished(pub(crate) Arc<Signal>);
+
+impl Future for WaitFinished {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0.is_set() {
+            return Poll::Ready(());
+        }
+
+        *self.0.waker.lock() = Some(cx.waker().clone());
+
+        // the callback may have fired before the waker was stored
+        if self.0.is_set() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    NotificationError,
+    logging::{self, Severity},
+};
+use ::slog::{Drain, KV, Key, OwnedKVList, Record, Serializer};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+/// [`slog::Drain`](::slog::Drain) which shows records as notifications.
+///
+/// Records are shown according to the [`Route`](logging::Route) of their tag or module and the
+/// [`LevelMap`](logging::LevelMap), criticals count as errors. Key/value pairs are attached as
+/// [`field`](crate::NotificationBuilder::field)s.
+pub struct NotificationDrain {
+    level: ::slog::Level,
+}
+
+impl Default for NotificationDrain {
+    fn default() -> Self {
+        Self::new(::slog::Level::Trace)
+    }
+}
+
+impl NotificationDrain {
+    /// Drain passing records of `level` and above to the [`LevelMap`](logging::LevelMap).
+    pub fn new(level: ::slog::Level) -> Self {
+        Self { level }
+    }
+}
+
+impl Drain for NotificationDrain {
+    type Ok = ();
+    type Err = NotificationError;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if !record.level().is_at_least(self.level) {
+            return Ok(());
+        }
+
+        let mut fields = Fields(Vec::new());
+        let _ = record.kv().serialize(record, &mut fields);
+        let _ = values.serialize(record, &mut fields);
+
+        let severity = match record.level() {
+            ::slog::Level::Critical | ::slog::Level::Error => Severity::Error,
+            ::slog::Level::Warning => Severity::Warn,
+            ::slog::Level::Info => Severity::Info,
+            ::slog::Level::Debug => Severity::Debug,
+            ::slog::Level::Trace => Severity::Trace,
+        };
+
+        let target = match record.tag() {
+            "" => record.module(),
+            tag => tag,
+        };
+
+        logging::dispatch(severity, target, &format!("{}", record.msg()), fields.0)
+    }
+}
+
+struct Fields(Vec<(Key, String)>);
+
+impl Serializer for Fields {
+    fn emit_arguments(&mut self, key: Key, val: &fmt::Arguments) -> ::slog::Result {
+        self.0.push((key, val.to_string()));
+        Ok(())
+    }
+}
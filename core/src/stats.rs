@@ -0,0 +1,197 @@
+use alloc::{format, string::String, vec::Vec};
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+    time::Duration,
+};
+use spin::Mutex;
+
+static SHOWN: AtomicU32 = AtomicU32::new(0);
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+static HEAP: AtomicUsize = AtomicUsize::new(0);
+static ERRORS: Mutex<Vec<(Option<&'static str>, u32)>> = Mutex::new(Vec::new());
+
+/// Counters collected since startup.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    /// Notifications accepted by the module.
+    pub shown: u32,
+    /// Notifications dropped by the [`NotificationManager`](crate::NotificationManager), e.g.
+    /// by a [`Rule`](crate::policy::Rule) or because they exceeded the max. age.
+    pub dropped: u32,
+    /// Error notifications shown per [`tag`](crate::NotificationBuilder::tag).
+    pub errors: Vec<(Option<&'static str>, u32)>,
+    /// Estimated heap usage of the [`NotificationManager`](crate::NotificationManager) in
+    /// bytes, see [`heap_budget`](crate::NotificationManager::heap_budget).
+    pub heap: usize,
+}
+
+impl Stats {
+    /// Error notifications shown across all tags.
+    pub fn total_errors(&self) -> u32 {
+        self.errors.iter().map(|(_, n)| n).sum()
+    }
+}
+
+/// Current counters.
+pub fn stats() -> Stats {
+    Stats {
+        shown: SHOWN.load(Ordering::Relaxed),
+        dropped: DROPPED.load(Ordering::Relaxed),
+        errors: ERRORS.lock().clone(),
+        heap: HEAP.load(Ordering::Relaxed),
+    }
+}
+
+pub(crate) fn shown() {
+    SHOWN.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn dropped(count: usize) {
+    DROPPED.fetch_add(count as u32, Ordering::Relaxed);
+}
+
+pub(crate) fn heap(bytes: usize) {
+    HEAP.store(bytes, Ordering::Relaxed);
+}
+
+pub(crate) fn error(tag: Option<&'static str>) {
+    let mut errors = ERRORS.lock();
+    match errors.iter_mut().find(|(t, _)| *t == tag) {
+        Some((_, n)) => *n += 1,
+        None => errors.push((tag, 1)),
+    }
+}
+
+// region: Statsd
+
+/// Periodically sends the counters as statsd packets, e.g. to monitor long playtest sessions
+/// from a PC.
+///
+/// Packets are passed to `send`, which transmits them over any transport, e.g. a UDP socket
+/// (see `udp_exporter` with the `std` feature).
+pub struct Exporter<F> {
+    send: F,
+    prefix: String,
+    interval: Duration,
+    last: Option<Duration>,
+    sent: Stats,
+}
+
+impl<F: FnMut(&[u8]) -> Result<(), E>, E> Exporter<F> {
+    /// Passes the packets to `send`, metric names start with `prefix`.
+    pub fn new(prefix: &str, send: F) -> Self {
+        Self {
+            send,
+            prefix: String::from(prefix),
+            interval: Duration::from_secs(10),
+            last: None,
+            sent: Stats::default(),
+        }
+    }
+
+    /// Minimum time between two exports, defaults to 10 s.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sends the counters if the interval elapsed, call periodically (e.g. once per frame).
+    pub fn update(&mut self) -> Result<(), E> {
+        let now = crate::clock::now();
+        if self
+            .last
+            .is_some_and(|last| now.saturating_sub(last) < self.interval)
+        {
+            return Ok(());
+        }
+        self.last = Some(now);
+        self.flush()
+    }
+
+    /// Sends the counters which changed since the last export as statsd counters, the heap
+    /// usage as gauge.
+    pub fn flush(&mut self) -> Result<(), E> {
+        let current = stats();
+        let packet = packet(&self.prefix, &current, &self.sent);
+        if !packet.is_empty() {
+            (self.send)(packet.as_bytes())?;
+        }
+        self.sent = current;
+
+        Ok(())
+    }
+}
+
+/// Exporter sending to `host` (e.g. `"192.168.0.10:8125"`) over UDP.
+#[cfg(feature = "std")]
+pub fn udp_exporter(
+    host: impl std::net::ToSocketAddrs,
+    prefix: &str,
+) -> std::io::Result<Exporter<impl FnMut(&[u8]) -> std::io::Result<()>>> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect(host)?;
+    socket.set_nonblocking(true)?;
+
+    Ok(Exporter::new(prefix, move |packet: &[u8]| {
+        socket.send(packet).map(|_| ())
+    }))
+}
+
+/// Statsd lines for the counters of `current` which changed since `sent`.
+fn packet(prefix: &str, current: &Stats, sent: &Stats) -> String {
+    let mut packet = String::new();
+
+    let mut counter = |name: &str, value: u32, sent: u32| {
+        if value > sent {
+            let _ = writeln!(packet, "{prefix}.{name}:{}|c", value - sent);
+        }
+    };
+    counter("shown", current.shown, sent.shown);
+    counter("dropped", current.dropped, sent.dropped);
+    for (tag, count) in &current.errors {
+        let sent = sent
+            .errors
+            .iter()
+            .find(|(t, _)| t == tag)
+            .map_or(0, |(_, n)| *n);
+        counter(
+            &format!("errors.{}", tag.unwrap_or("untagged")),
+            *count,
+            sent,
+        );
+    }
+
+    if current.heap != sent.heap {
+        let _ = writeln!(packet, "{prefix}.heap:{}|g", current.heap);
+    }
+
+    packet
+}
+
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packets() {
+        let sent = Stats {
+            shown: 2,
+            errors: alloc::vec![(Some("net"), 1)],
+            ..Default::default()
+        };
+        let current = Stats {
+            shown: 5,
+            dropped: 0,
+            errors: alloc::vec![(Some("net"), 1), (None, 2)],
+            heap: 64,
+        };
+        assert_eq!(
+            packet("app", &current, &sent),
+            "app.shown:3|c\napp.errors.untagged:2|c\napp.heap:64|g\n"
+        );
+        assert!(packet("app", &sent, &sent).is_empty());
+    }
+}
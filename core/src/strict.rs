@@ -0,0 +1,73 @@
+use crate::NotificationError;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use spin::Mutex;
+
+/// Receives every error ignored by the crate while strict mode is enabled.
+pub type Handler = fn(&NotificationError);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static IGNORED: AtomicU32 = AtomicU32::new(0);
+static HANDLER: Mutex<Option<Handler>> = Mutex::new(None);
+/// Last ignored error per OSThread.
+static LAST: Mutex<Vec<(usize, NotificationError)>> = Mutex::new(Vec::new());
+
+/// Records errors which the crate would otherwise silently ignore, e.g. while finishing a
+/// notification or showing an internal warning.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Sets the function called with every recorded error, e.g. to log it.
+pub fn set_handler(handler: Option<Handler>) {
+    *HANDLER.lock() = handler;
+}
+
+/// Number of errors recorded since strict mode was first enabled.
+pub fn ignored_count() -> u32 {
+    IGNORED.load(Ordering::Relaxed)
+}
+
+/// Last error recorded on the calling thread.
+pub fn last_error() -> Option<NotificationError> {
+    let thread = current_thread();
+    LAST.lock()
+        .iter()
+        .find(|(t, _)| *t == thread)
+        .map(|(_, e)| e.clone())
+}
+
+/// Discards the result, recording the error if strict mode is enabled.
+pub(crate) fn ignore<T>(result: Result<T, NotificationError>) {
+    let Err(error) = result else {
+        return;
+    };
+
+    if !is_enabled() {
+        return;
+    }
+
+    IGNORED.fetch_add(1, Ordering::Relaxed);
+    if let Some(handler) = crate::callback(&HANDLER) {
+        handler(&error);
+    }
+
+    let thread = current_thread();
+    let mut last = LAST.lock();
+    match last.iter_mut().find(|(t, _)| *t == thread) {
+        Some((_, e)) => *e = error,
+        None => last.push((thread, error)),
+    }
+}
+
+fn current_thread() -> usize {
+    unsafe { wut::bindings::OSGetCurrentThread() as usize }
+}
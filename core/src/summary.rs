@@ -0,0 +1,98 @@
+use crate::{Level, NotificationError, error, info};
+use alloc::format;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static WARNINGS: AtomicU32 = AtomicU32::new(0);
+static ERRORS: AtomicU32 = AtomicU32::new(0);
+
+/// Starts counting warnings and errors for the session summary.
+///
+/// Error notifications are counted automatically, other sources report through [`record`].
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Stops counting, the counts collected so far are kept.
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Disables the summary and drops the counts.
+#[cfg(feature = "mock")]
+pub(crate) fn reset() {
+    disable();
+    WARNINGS.store(0, Ordering::Relaxed);
+    ERRORS.store(0, Ordering::Relaxed);
+}
+
+/// Counts a message of the given level, ignored if the summary is disabled.
+pub fn record(level: Level) {
+    if !is_enabled() {
+        return;
+    }
+
+    match level {
+        Level::Info => {}
+        Level::Warn => {
+            WARNINGS.fetch_add(1, Ordering::Relaxed);
+        }
+        Level::Error => {
+            ERRORS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Number of warnings and errors counted so far.
+pub fn counts() -> (u32, u32) {
+    (
+        WARNINGS.load(Ordering::Relaxed),
+        ERRORS.load(Ordering::Relaxed),
+    )
+}
+
+/// Shows the summary, e.g. `"2 errors, 5 warnings this session - see log"`, and resets the
+/// counts.
+///
+/// Nothing is shown if neither warnings nor errors were counted.
+pub fn flush() -> Result<(), NotificationError> {
+    let warnings = WARNINGS.swap(0, Ordering::Relaxed);
+    let errors = ERRORS.swap(0, Ordering::Relaxed);
+
+    // the summary itself must not be counted
+    let enabled = ENABLED.swap(false, Ordering::Relaxed);
+    let result = show(warnings, errors);
+    ENABLED.store(enabled, Ordering::Relaxed);
+
+    result
+}
+
+fn show(warnings: u32, errors: u32) -> Result<(), NotificationError> {
+    let plural = |n: u32| if n == 1 { "" } else { "s" };
+    match (warnings, errors) {
+        (0, 0) => {}
+        (warnings, 0) => {
+            info(&format!(
+                "{warnings} warning{} this session - see log",
+                plural(warnings)
+            ))
+            .show()?;
+        }
+        (warnings, errors) => {
+            // shown without shake, the errors themselves have been shown already
+            error(&format!(
+                "{errors} error{}, {warnings} warning{} this session - see log",
+                plural(errors),
+                plural(warnings)
+            ))
+            .shake(None)
+            .show()?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::{Notification, NotificationError, dynamic};
+use alloc::{string::String, vec::Vec};
+use core::fmt::{Display, Write};
+
+/// Dynamic notification rendered from a pattern with `{}` placeholders, e.g. `"FPS: {}"`.
+///
+/// Meant for per-frame HUDs: formatting and the module call are skipped while the values don't
+/// change, and the text buffer is reused between renders.
+pub struct Template<V: PartialEq + Clone + Display> {
+    parts: Vec<&'static str>,
+    values: Vec<V>,
+    buffer: String,
+    notification: Notification,
+}
+
+impl<V: PartialEq + Clone + Display> Template<V> {
+    /// Shows the pattern with empty placeholders.
+    pub fn new(pattern: &'static str) -> Result<Self, NotificationError> {
+        let parts: Vec<_> = pattern.split("{}").collect();
+        let buffer = parts.concat();
+        Ok(Self {
+            notification: dynamic(&buffer).show()?,
+            parts,
+            values: Vec::new(),
+            buffer,
+        })
+    }
+
+    /// Substitutes the placeholders in order, missing values are left empty.
+    ///
+    /// Does nothing if the values equal the ones of the last successful call.
+    pub fn set(&mut self, values: &[V]) -> Result<(), NotificationError> {
+        if values == self.values.as_slice() {
+            return Ok(());
+        }
+
+        self.buffer.clear();
+        for (i, part) in self.parts.iter().enumerate() {
+            if i > 0 {
+                if let Some(value) = values.get(i - 1) {
+                    let _ = write!(self.buffer, "{value}");
+                }
+            }
+            self.buffer.push_str(part);
+        }
+        self.notification.text(&self.buffer)?;
+
+        self.values.clear();
+        self.values.extend_from_slice(values);
+        Ok(())
+    }
+
+    pub fn notification(&self) -> &Notification {
+        &self.notification
+    }
+}
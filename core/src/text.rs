@@ -0,0 +1,332 @@
+use alloc::{borrow::Cow, string::String};
+use core::fmt::Write;
+
+// region: List
+
+/// Joins items with `", "` and `" and "`, at most 3 items are listed.
+///
+/// `["a", "b", "c", "d", "e"]` becomes `"a, b and 3 more…"`.
+pub fn text_list<S: AsRef<str>>(items: &[S]) -> String {
+    text_list_limit(items, 3)
+}
+
+/// Joins items like [`text_list`], with at most `limit` (min. 2) entries including the
+/// `"N more…"` suffix.
+pub fn text_list_limit<S: AsRef<str>>(items: &[S], limit: usize) -> String {
+    let limit = limit.max(2);
+    let (shown, rest) = if items.len() > limit {
+        (&items[..limit - 1], items.len() - (limit - 1))
+    } else {
+        (items, 0)
+    };
+
+    let mut text = String::new();
+    for (i, item) in shown.iter().enumerate() {
+        if i > 0 {
+            let last = rest == 0 && i == shown.len() - 1;
+            text.push_str(if last { " and " } else { ", " });
+        }
+        text.push_str(item.as_ref());
+    }
+
+    if rest > 0 {
+        let _ = write!(text, " and {rest} more\u{2026}");
+    }
+
+    text
+}
+
+// endregion
+
+// region: Table
+
+/// Pads keys to a common width so the values line up, one row per line.
+///
+/// `[("FPS", "60"), ("Frame", "16.6 ms")]` becomes `"FPS   : 60\nFrame : 16.6 ms"`.
+pub fn table<K: AsRef<str>, V: AsRef<str>>(rows: &[(K, V)]) -> String {
+    let width = rows
+        .iter()
+        .map(|(key, _)| key.as_ref().chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (i, (key, value)) in rows.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        let key = key.as_ref();
+        let _ = write!(
+            text,
+            "{key}{:pad$} : {}",
+            "",
+            value.as_ref(),
+            pad = width - key.chars().count()
+        );
+    }
+
+    text
+}
+
+// endregion
+
+// region: Newlines
+
+/// Handling of line breaks in notification text, see [`Theme::newlines`](crate::Theme::newlines).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Newlines {
+    /// Pass line breaks to the overlay, for module versions rendering multiple lines.
+    #[default]
+    Keep,
+    /// Remove line breaks.
+    Strip,
+    /// Replace line breaks with `" | "`.
+    Replace,
+}
+
+/// Applies the newline handling to `text`, `"\r\n"` counts as a single line break.
+pub(crate) fn sanitize(text: &str, newlines: Newlines) -> Cow<'_, str> {
+    let separator = match newlines {
+        Newlines::Keep => return Cow::Borrowed(text),
+        Newlines::Strip => "",
+        Newlines::Replace => " | ",
+    };
+    if !text.contains(['\r', '\n']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            out.push_str(separator);
+        }
+        out.push_str(line);
+    }
+    Cow::Owned(out)
+}
+
+// endregion
+
+// region: Markup
+
+/// Rendering of inline markup, see [`Theme::markup`](crate::Theme::markup).
+///
+/// The overlay draws a notification in a single color, so `*emphasis*` and `` `code` `` are
+/// rendered with brackets and quotes. Unpaired markers are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Markup {
+    /// Show markers as typed.
+    #[default]
+    Off,
+    /// `*important*` becomes `"[important]"`, `` `code` `` becomes `"'code'"`.
+    Brackets,
+    /// Remove the markers.
+    Strip,
+}
+
+/// Renders the markup of `text`.
+pub(crate) fn markup(text: &str, markup: Markup) -> Cow<'_, str> {
+    if markup == Markup::Off || !text.contains(['*', '`']) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(['*', '`']) {
+        let marker = rest[start..].chars().next().unwrap_or('*');
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        match after.find(marker) {
+            Some(end) if end > 0 => {
+                let (open, close) = match (markup, marker) {
+                    (Markup::Brackets, '*') => ("[", "]"),
+                    (Markup::Brackets, _) => ("'", "'"),
+                    _ => ("", ""),
+                };
+                out.push_str(open);
+                out.push_str(&after[..end]);
+                out.push_str(close);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push(marker);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+// endregion
+
+// region: Glyphs
+
+/// Replacements for characters the overlay font can't render, see
+/// [`Theme::glyphs`](crate::Theme::glyphs).
+#[derive(Debug, Clone, Copy)]
+pub struct Glyphs {
+    /// Explicit replacements, checked first.
+    pub map: &'static [(char, &'static str)],
+    /// Replacement of other emoji, arrows and symbols, `""` removes them.
+    pub unsupported: &'static str,
+    /// The font renders Chinese, Japanese and Korean characters.
+    pub cjk: bool,
+}
+
+impl Glyphs {
+    /// Arrows, check marks and typographic quotes mapped to ASCII.
+    pub const DEFAULT_MAP: &'static [(char, &'static str)] = &[
+        ('\u{2190}', "<-"),
+        ('\u{2192}', "->"),
+        ('\u{2191}', "^"),
+        ('\u{2193}', "v"),
+        ('\u{2194}', "<->"),
+        ('\u{21D0}', "<="),
+        ('\u{21D2}', "=>"),
+        ('\u{2713}', "ok"),
+        ('\u{2714}', "ok"),
+        ('\u{2717}', "x"),
+        ('\u{2718}', "x"),
+        ('\u{2605}', "*"),
+        ('\u{2022}', "*"),
+        ('\u{2018}', "'"),
+        ('\u{2019}', "'"),
+        ('\u{201C}', "\""),
+        ('\u{201D}', "\""),
+        ('\u{2013}', "-"),
+        ('\u{2014}', "-"),
+        ('\u{26A0}', "(!)"),
+    ];
+
+    fn replacement(&self, c: char) -> Option<&'static str> {
+        if let Some((_, s)) = self.map.iter().find(|(m, _)| *m == c) {
+            return Some(s);
+        }
+
+        let unsupported = matches!(c,
+            // arrows
+            '\u{2190}'..='\u{21FF}'
+            // misc. symbols and dingbats
+            | '\u{2600}'..='\u{27BF}'
+            // emoji and pictographs
+            | '\u{1F000}'..='\u{1FAFF}'
+            // joiners and variation selectors of emoji sequences
+            | '\u{200D}' | '\u{FE0E}' | '\u{FE0F}'
+        ) || (!self.cjk
+            && matches!(c,
+                '\u{3000}'..='\u{30FF}'
+                | '\u{3400}'..='\u{4DBF}'
+                | '\u{4E00}'..='\u{9FFF}'
+                | '\u{AC00}'..='\u{D7AF}'
+                | '\u{FF00}'..='\u{FFEF}'
+            ));
+
+        unsupported.then_some(self.unsupported)
+    }
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self {
+            map: Self::DEFAULT_MAP,
+            unsupported: "?",
+            cjk: true,
+        }
+    }
+}
+
+/// Replaces characters the font can't render according to `glyphs`.
+pub(crate) fn fallback<'a>(text: &'a str, glyphs: Option<&Glyphs>) -> Cow<'a, str> {
+    let Some(glyphs) = glyphs else {
+        return Cow::Borrowed(text);
+    };
+    if text.is_ascii() || !text.chars().any(|c| glyphs.replacement(c).is_some()) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match glyphs.replacement(c) {
+            Some(s) => out.push_str(s),
+            None => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists() {
+        assert_eq!(text_list::<&str>(&[]), "");
+        assert_eq!(text_list(&["a"]), "a");
+        assert_eq!(text_list(&["a", "b"]), "a and b");
+        assert_eq!(text_list(&["a", "b", "c"]), "a, b and c");
+        assert_eq!(
+            text_list(&["a", "b", "c", "d", "e"]),
+            "a, b and 3 more\u{2026}"
+        );
+        assert_eq!(text_list_limit(&["a", "b", "c"], 0), "a and 2 more\u{2026}");
+    }
+
+    #[test]
+    fn tables() {
+        assert_eq!(
+            table(&[("FPS", "60"), ("Frame", "16.6 ms")]),
+            "FPS   : 60\nFrame : 16.6 ms"
+        );
+        assert_eq!(table::<&str, &str>(&[]), "");
+    }
+
+    #[test]
+    fn newlines() {
+        let text = "a\r\nb\nc";
+        assert!(matches!(sanitize(text, Newlines::Keep), Cow::Borrowed(_)));
+        assert_eq!(sanitize(text, Newlines::Strip), "abc");
+        assert_eq!(sanitize(text, Newlines::Replace), "a | b | c");
+        assert!(matches!(
+            sanitize("abc", Newlines::Replace),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn markups() {
+        let text = "a *b* `c`";
+        assert!(matches!(markup(text, Markup::Off), Cow::Borrowed(_)));
+        assert_eq!(markup(text, Markup::Brackets), "a [b] 'c'");
+        assert_eq!(markup(text, Markup::Strip), "a b c");
+        assert_eq!(markup("2 * 3 = 6", Markup::Brackets), "2 * 3 = 6");
+        assert_eq!(markup("**", Markup::Brackets), "**");
+    }
+
+    #[test]
+    fn glyphs() {
+        let glyphs = Glyphs::default();
+        assert_eq!(
+            fallback("\u{2192} done \u{2713}", Some(&glyphs)),
+            "-> done ok"
+        );
+        assert_eq!(fallback("play \u{1F3AE}", Some(&glyphs)), "play ?");
+        assert!(matches!(
+            fallback("\u{65E5}\u{672C}", Some(&glyphs)),
+            Cow::Borrowed(_)
+        ));
+
+        let latin = Glyphs {
+            cjk: false,
+            unsupported: "",
+            ..glyphs
+        };
+        assert_eq!(fallback("\u{65E5}\u{672C}!", Some(&latin)), "!");
+        assert!(matches!(fallback("\u{2192}", None), Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,269 @@
+use crate::{
+    LIVE, NOTIFY, NotificationError, Operation,
+    text::{Glyphs, Markup, Newlines},
+    trace,
+};
+use core::time::Duration;
+use notifications_sys as sys;
+use spin::Mutex;
+use wut::gx2::color::Color;
+
+static THEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+/// Styling used by notifications which don't set their own.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub text_color: Color,
+    pub background_color: Color,
+    /// Time before fading out.
+    pub duration: Duration,
+    /// Prepended to the text of info and dynamic notifications.
+    pub prefix: &'static str,
+    /// Prefix the text with the name of the calling thread, e.g. `"[loader] "`.
+    pub thread_name: bool,
+    /// Prefix the text with the current time, e.g. `"[14:02:55] "`.
+    pub timestamp: bool,
+    /// Text color of [`error`](crate::error) notifications.
+    pub error_text_color: Color,
+    /// Background color of [`error`](crate::error) notifications.
+    pub error_background_color: Color,
+    /// Time before [`error`](crate::error) notifications fade out.
+    pub error_duration: Duration,
+    /// Shake duration of [`error`](crate::error) notifications.
+    pub error_shake: Option<Duration>,
+    /// Prepended to the text of [`error`](crate::error) notifications.
+    pub error_prefix: &'static str,
+    /// Upper bound for the duration, shake and delay of every notification, so no caller can
+    /// keep a notification on screen indefinitely.
+    pub max_duration: Option<Duration>,
+    /// Handling of line breaks in the text of new and updated notifications.
+    pub newlines: Newlines,
+    /// Rendering of `*emphasis*` and `` `code` `` in the text of notifications.
+    pub markup: Markup,
+    /// Replacements for characters the overlay font can't render, e.g.
+    /// `Some(Glyphs::default())`. `None`, the default, passes them through unchanged.
+    pub glyphs: Option<Glyphs>,
+    /// Reading speed used by [`auto_duration`](crate::NotificationBuilder::auto_duration).
+    pub reading: ReadingRate,
+}
+
+/// Reading speed to derive display durations from the text length.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ReadingRate {
+    pub words_per_second: f32,
+    /// Lower bound, so short messages can still be noticed.
+    pub min: Duration,
+    /// Upper bound, so long messages don't linger.
+    pub max: Duration,
+}
+
+impl Default for ReadingRate {
+    /// 3 words per second, at least 3 s and at most 15 s.
+    fn default() -> Self {
+        Self {
+            words_per_second: 3.0,
+            min: Duration::from_secs(3),
+            max: Duration::from_secs(15),
+        }
+    }
+}
+
+impl ReadingRate {
+    /// Time to read `text`.
+    pub fn duration(&self, text: &str) -> Duration {
+        let words = text.split_whitespace().count() as f32;
+        let secs = words / self.words_per_second.max(0.1);
+        Duration::from_secs_f32(secs).clamp(self.min, self.max.max(self.min))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            text_color: Color::white(),
+            background_color: Color::black().opacity(0.5).into(),
+            duration: Duration::from_secs(5),
+            prefix: "",
+            thread_name: false,
+            timestamp: false,
+            error_text_color: Color::white(),
+            error_background_color: Color::red(),
+            error_duration: Duration::from_secs(5),
+            error_shake: Some(Duration::from_secs(1)),
+            error_prefix: "",
+            max_duration: None,
+            newlines: Newlines::Keep,
+            markup: Markup::Off,
+            glyphs: None,
+            reading: ReadingRate::default(),
+        }
+    }
+}
+
+impl Theme {
+    /// Palette distinguishable with deuteranopia and protanopia.
+    ///
+    /// Uses blue for info and orange for errors (Okabe-Ito) and marks the severity with a
+    /// prefix, so it never relies on color alone.
+    pub fn color_blind() -> Self {
+        Self {
+            background_color: Color {
+                r: 0x00,
+                g: 0x72,
+                b: 0xB2,
+                a: 0xC0,
+            },
+            prefix: "(i) ",
+            error_text_color: Color::black(),
+            error_background_color: Color {
+                r: 0xE6,
+                g: 0x9F,
+                b: 0x00,
+                a: 0xFF,
+            },
+            error_prefix: "(!) ",
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Theme {
+    fn format(&self, f: defmt::Formatter) {
+        let rgba = |c: Color| [c.r, c.g, c.b, c.a];
+        defmt::write!(
+            f,
+            "Theme {{ text_color: {=[u8; 4]:#04x}, background_color: {=[u8; 4]:#04x}, duration: {=u128}ms, prefix: {=str}, thread_name: {=bool}, timestamp: {=bool}, error_text_color: {=[u8; 4]:#04x}, error_background_color: {=[u8; 4]:#04x}, error_duration: {=u128}ms, error_shake: {}, error_prefix: {=str}, max_duration: {}, newlines: {}, markup: {}, glyphs: {=bool}, reading: {} }}",
+            rgba(self.text_color),
+            rgba(self.background_color),
+            self.duration.as_millis(),
+            self.prefix,
+            self.thread_name,
+            self.timestamp,
+            rgba(self.error_text_color),
+            rgba(self.error_background_color),
+            self.error_duration.as_millis(),
+            self.error_shake.map(|d| d.as_millis() as u64),
+            self.error_prefix,
+            self.max_duration.map(|d| d.as_millis() as u64),
+            self.newlines,
+            self.markup,
+            self.glyphs.is_some(),
+            self.reading,
+        )
+    }
+}
+
+/// Currently active theme.
+pub fn theme() -> Theme {
+    THEME.lock().unwrap_or_default()
+}
+
+/// Currently active theme, `None` while it is locked, e.g. by the code which panicked.
+pub(crate) fn try_theme() -> Option<Theme> {
+    THEME.try_lock().map(|theme| theme.unwrap_or_default())
+}
+
+/// Replaces the active theme.
+///
+/// If `live` is set, the colors are also applied to all live dynamic notifications. A
+/// notification the module rejects doesn't stop the others from being updated, the first error
+/// is returned afterwards.
+pub fn set_theme(theme: Theme, live: bool) -> Result<(), NotificationError> {
+    *THEME.lock() = Some(theme);
+
+    if !live {
+        return Ok(());
+    }
+
+    let _r = NOTIFY.acquire();
+    let mut result = Ok(());
+    for (_, handle) in LIVE.lock().iter().copied() {
+        trace::call(
+            Operation::UpdateDynamicNotificationTextColor,
+            format_args!("{handle}, {:?}", theme.text_color),
+        );
+        let status = unsafe {
+            sys::NotificationModule_UpdateDynamicNotificationTextColor(
+                handle,
+                sys::NMColor {
+                    r: theme.text_color.r,
+                    g: theme.text_color.g,
+                    b: theme.text_color.b,
+                    a: theme.text_color.a,
+                },
+            )
+        };
+        result = result.and(NotificationError::check(
+            status,
+            Operation::UpdateDynamicNotificationTextColor,
+        ));
+
+        trace::call(
+            Operation::UpdateDynamicNotificationBackgroundColor,
+            format_args!("{handle}, {:?}", theme.background_color),
+        );
+        let status = unsafe {
+            sys::NotificationModule_UpdateDynamicNotificationBackgroundColor(
+                handle,
+                sys::NMColor {
+                    r: theme.background_color.r,
+                    g: theme.background_color.g,
+                    b: theme.background_color.b,
+                    a: theme.background_color.a,
+                },
+            )
+        };
+        result = result.and(NotificationError::check(
+            status,
+            Operation::UpdateDynamicNotificationBackgroundColor,
+        ));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_rate() {
+        let rate = ReadingRate::default();
+        assert_eq!(rate.duration("ok"), rate.min);
+        assert_eq!(rate.duration(&"word ".repeat(30)), Duration::from_secs(10));
+        assert_eq!(rate.duration(&"word ".repeat(100)), rate.max);
+
+        let inverted = ReadingRate {
+            max: Duration::from_secs(1),
+            ..rate
+        };
+        assert_eq!(inverted.duration(&"word ".repeat(100)), rate.min);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn live_continues() {
+        use crate::mock::{Call, MockBackend, Simulation};
+
+        let _sim = Simulation::start();
+        let _a = crate::dynamic("a").show().unwrap();
+        let _b = crate::dynamic("b").show().unwrap();
+        MockBackend::fail_next(
+            sys::NotificationModuleStatus::NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT,
+        );
+        assert!(set_theme(Theme::default(), true).is_err());
+
+        let updates = MockBackend::calls()
+            .into_iter()
+            .filter(|call| {
+                matches!(
+                    call,
+                    Call::UpdateTextColor { .. } | Call::UpdateBackgroundColor { .. }
+                )
+            })
+            .count();
+        assert_eq!(updates, 3);
+    }
+}
@@ -0,0 +1,17 @@
+use alloc::string::String;
+use core::ffi::CStr;
+
+/// Name of the calling OSThread, `None` if it has no name.
+pub fn current_name() -> Option<String> {
+    let name = unsafe { wut::bindings::OSGetThreadName(wut::bindings::OSGetCurrentThread()) };
+    if name.is_null() {
+        return None;
+    }
+
+    let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.into_owned())
+    }
+}
@@ -0,0 +1,49 @@
+use crate::Operation;
+use core::fmt::Arguments;
+#[cfg(feature = "trace-ffi")]
+use spin::Mutex;
+
+/// Receives one formatted line per traced call and result.
+pub type Sink = fn(Arguments);
+
+#[cfg(feature = "trace-ffi")]
+static SINK: Mutex<Option<Sink>> = Mutex::new(None);
+
+/// Sets the receiver of FFI traces, e.g. a function forwarding to the wut logger.
+///
+/// Tracing is only compiled in with the `trace-ffi` feature, otherwise the sink is never
+/// called.
+pub fn set_sink(sink: Option<Sink>) {
+    #[cfg(feature = "trace-ffi")]
+    {
+        *SINK.lock() = sink;
+    }
+    #[cfg(not(feature = "trace-ffi"))]
+    let _ = sink;
+}
+
+/// Traces a call of `operation` with its arguments.
+#[inline]
+pub(crate) fn call(operation: Operation, args: Arguments) {
+    #[cfg(feature = "trace-ffi")]
+    {
+        if let Some(sink) = crate::callback(&SINK) {
+            sink(format_args!("-> {operation:?}({args})"));
+        }
+    }
+    #[cfg(not(feature = "trace-ffi"))]
+    let _ = (operation, args);
+}
+
+/// Traces the status returned by `operation`.
+#[inline]
+pub(crate) fn result(operation: Operation, status: i32) {
+    #[cfg(feature = "trace-ffi")]
+    {
+        if let Some(sink) = crate::callback(&SINK) {
+            sink(format_args!("<- {operation:?} = {status}"));
+        }
+    }
+    #[cfg(not(feature = "trace-ffi"))]
+    let _ = (operation, status);
+}
@@ -0,0 +1,145 @@
+use crate::{Notification, NotificationError, clock, idle::IdleSlot};
+use alloc::{collections::VecDeque, format, string::String};
+use core::time::Duration;
+
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Dynamic notification summarizing a stream of samples.
+///
+/// Renders as `"{label}: 16.7 (min 16.5, max 33.4, avg 17.1)"`, followed by a sparkline like
+/// `" ▁▂▃▅▇"` if enabled.
+pub struct Tracker {
+    label: String,
+    current: f32,
+    min: f32,
+    max: f32,
+    sum: f64,
+    count: u32,
+    recent: VecDeque<f32>,
+    sparkline: usize,
+    interval: Duration,
+    updated: Option<Duration>,
+    notification: IdleSlot,
+}
+
+impl Tracker {
+    /// Shows a new tracker without samples, updated at most every 250 ms.
+    pub fn new(label: &str) -> Result<Self, NotificationError> {
+        Ok(Self {
+            label: String::from(label),
+            current: 0.0,
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            sum: 0.0,
+            count: 0,
+            recent: VecDeque::new(),
+            sparkline: 0,
+            interval: Duration::from_millis(250),
+            updated: None,
+            notification: IdleSlot::new(label)?,
+        })
+    }
+
+    /// Minimum time between two updates of the notification.
+    pub fn throttle(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Show a sparkline of the last `samples` samples, 0 disables it.
+    pub fn sparkline(&mut self, samples: usize) {
+        self.sparkline = samples;
+        while self.recent.len() > samples {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Records a sample, the notification is only updated if the throttle interval passed.
+    pub fn push(&mut self, sample: f32) -> Result<(), NotificationError> {
+        self.current = sample;
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+        self.sum += sample as f64;
+        self.count += 1;
+
+        if self.sparkline > 0 {
+            if self.recent.len() == self.sparkline {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(sample);
+        }
+
+        let now = clock::now();
+        match self.updated {
+            Some(updated) if now.saturating_sub(updated) < self.interval => Ok(()),
+            _ => {
+                self.updated = Some(now);
+                self.notification.text(&self.render())
+            }
+        }
+    }
+
+    /// Forgets all samples.
+    pub fn reset(&mut self) -> Result<(), NotificationError> {
+        self.current = 0.0;
+        self.min = f32::INFINITY;
+        self.max = f32::NEG_INFINITY;
+        self.sum = 0.0;
+        self.count = 0;
+        self.recent.clear();
+        self.updated = None;
+        self.notification.text(&self.label)
+    }
+
+    /// Average of all samples, `None` without samples.
+    pub fn avg(&self) -> Option<f32> {
+        (self.count > 0).then(|| (self.sum / self.count as f64) as f32)
+    }
+
+    /// Finish the notification after `timeout` without samples, it is shown again with the next
+    /// sample. Requires calling [`poll`](Self::poll) periodically.
+    pub fn idle_timeout(&mut self, timeout: Option<Duration>) {
+        self.notification.set_timeout(timeout);
+    }
+
+    /// Finishes the notification if the [`idle_timeout`](Self::idle_timeout) passed.
+    pub fn poll(&mut self) {
+        self.notification.poll();
+    }
+
+    /// Underlying dynamic notification, `None` while idle.
+    pub fn notification(&self) -> Option<&Notification> {
+        self.notification.get()
+    }
+
+    fn render(&self) -> String {
+        let mut text = format!(
+            "{}: {:.1} (min {:.1}, max {:.1}, avg {:.1})",
+            self.label,
+            self.current,
+            self.min,
+            self.max,
+            self.avg().unwrap_or_default()
+        );
+
+        if !self.recent.is_empty() {
+            let low = self.recent.iter().copied().fold(f32::INFINITY, f32::min);
+            let high = self
+                .recent
+                .iter()
+                .copied()
+                .fold(f32::NEG_INFINITY, f32::max);
+            let range = high - low;
+
+            text.push(' ');
+            for sample in &self.recent {
+                let level = match range > 0.0 {
+                    true => ((sample - low) / range * (BARS.len() - 1) as f32) as usize,
+                    false => 0,
+                };
+                text.push(BARS[level.min(BARS.len() - 1)]);
+            }
+        }
+
+        text
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{NOTIFY, NotificationError, Operation, trace};
+use core::fmt;
+use notifications_sys as sys;
+
+/// API version of the installed NotificationModule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Version(pub u32);
+
+impl Version {
+    /// First version reporting whether the overlay is ready.
+    pub const OVERLAY_READY: Self = Self(2);
+
+    /// The module can report [`is_overlay_ready`](crate::is_overlay_ready).
+    pub fn supports_overlay_ready(self) -> bool {
+        self >= Self::OVERLAY_READY
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+/// Version of the installed module, fails if the module is missing.
+pub fn module_version() -> Result<Version, NotificationError> {
+    let _r = NOTIFY.acquire();
+    query()
+}
+
+/// [`module_version`], failing with [`UnsupportedVersion`] if it is older than `min`.
+///
+/// [`UnsupportedVersion`]: NotificationError::UnsupportedVersion
+pub fn require_version(min: Version) -> Result<Version, NotificationError> {
+    let version = module_version()?;
+    if version < min {
+        return Err(NotificationError::UnsupportedVersion(Operation::GetVersion));
+    }
+    Ok(version)
+}
+
+/// Queries the version, the module must be initialized.
+pub(crate) fn query() -> Result<Version, NotificationError> {
+    let mut version = 0;
+    trace::call(Operation::GetVersion, format_args!(""));
+    let status = unsafe { sys::NotificationModule_GetVersion(&mut version) };
+    NotificationError::check(status, Operation::GetVersion)?;
+    if version == sys::NOTIFICATION_MODULE_API_VERSION_ERROR {
+        return Err(NotificationError::UnsupportedVersion(Operation::GetVersion));
+    }
+    Ok(Version(version))
+}
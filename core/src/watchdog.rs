@@ -0,0 +1,73 @@
+use crate::{clock, error};
+use alloc::{format, string::String, vec::Vec};
+use core::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+use spin::Mutex;
+
+static ARMED: Mutex<Vec<Armed>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+struct Armed {
+    id: u32,
+    label: String,
+    deadline: Duration,
+}
+
+/// Raises an error notification if an operation takes longer than expected.
+///
+/// Nothing is shown if the watchdog is disarmed or dropped before the deadline. Deadlines are
+/// checked by [`NotificationManager::update`](crate::NotificationManager::update).
+///
+/// ```ignore
+/// let watchdog = Watchdog::arm("Saving", Duration::from_secs(5));
+/// save()?;
+/// watchdog.disarm();
+/// ```
+pub struct Watchdog {
+    id: u32,
+}
+
+impl Watchdog {
+    pub fn arm(label: &str, timeout: Duration) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        ARMED.lock().push(Armed {
+            id,
+            label: String::from(label),
+            deadline: clock::now() + timeout,
+        });
+        Self { id }
+    }
+
+    /// Stops the watchdog, equivalent to dropping it.
+    pub fn disarm(self) {}
+
+    /// Whether the deadline passed and the error was raised.
+    pub fn fired(&self) -> bool {
+        !ARMED.lock().iter().any(|a| a.id == self.id)
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        ARMED.lock().retain(|a| a.id != self.id);
+    }
+}
+
+/// Queues the errors of all watchdogs whose deadline passed.
+pub(crate) fn poll(manager: &mut crate::NotificationManager) {
+    let now = clock::now();
+    let mut expired = Vec::new();
+    ARMED.lock().retain(|a| {
+        if a.deadline > now {
+            return true;
+        }
+        expired.push(format!("{} is taking longer than expected…", a.label));
+        false
+    });
+
+    for text in expired {
+        manager.submit(error(&text));
+    }
+}
@@ -0,0 +1,14 @@
+//! Versioned binary format for mirroring notifications to PC tooling, e.g. from a
+//! [`Sink`](crate::policy::Sink) sending over UDP or appending to a file.
+//!
+//! Re-exported from the dependency-free `notifications-wire` crate, which PC tooling depends on
+//! to decode the records without the console toolchain.
+
+use crate::clock;
+
+pub use notifications_wire::*;
+
+/// Record of `text` with default colors, timestamped now.
+pub fn record(kind: Kind, text: &str) -> Record {
+    Record::new(kind, text, clock::now())
+}
@@ -0,0 +1,41 @@
+//! Hooks for the lifecycle of Aroma (WUPS) plugins.
+//!
+//! Call [`on_application_start`] and [`on_application_end`] from the plugin's
+//! `ON_APPLICATION_START` and `ON_APPLICATION_ENDS` hooks.
+
+use crate::{NOTIFY, NotificationError, logging, manager, shutdown, summary};
+use spin::Mutex;
+use wut::rrc::RrcGuard;
+
+/// Keeps the module initialized while an application is running.
+static GUARD: Mutex<Option<Guard>> = Mutex::new(None);
+
+struct Guard {
+    _guard: RrcGuard,
+}
+
+unsafe impl Send for Guard {}
+
+/// Initializes the module for the new application and shows notifications queued in the
+/// [`NotificationManager`](crate::NotificationManager) while no application was running.
+pub fn on_application_start() -> Result<(), NotificationError> {
+    GUARD.lock().get_or_insert_with(|| Guard {
+        _guard: NOTIFY.acquire(),
+    });
+    manager().update()
+}
+
+/// Shows pending burst summaries and the [`summary`] of the session, tears down all live
+/// notifications and releases the module.
+///
+/// Notifications still queued in the [`NotificationManager`](crate::NotificationManager) are
+/// kept and shown by [`on_application_start`] of the next application. Errors are reported after
+/// the module was released.
+pub fn on_application_end() -> Result<(), NotificationError> {
+    let flushed = logging::flush().and(summary::flush());
+    let pending = manager().take_pending();
+    let result = shutdown();
+    manager().restore_pending(pending);
+    GUARD.lock().take();
+    flushed.and(result)
+}
@@ -1,4 +1,4 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 pub use notifications_core::*;
 pub use notifications_sys as sys;
@@ -6,10 +6,23 @@ use std::{env, fs};
 
 const MIN_VERSION: Version = Version::new(14, 2, 0);
 
+/// Feature of each module API level above 1 and the functions it adds. A new level gets a
+/// `module-api-N` feature here, in both manifests and on its stubs in the mock of
+/// notifications-core.
+const API_LEVELS: &[(&str, &[&str])] = &[(
+    "CARGO_FEATURE_MODULE_API_2",
+    &["NotificationModule_IsOverlayReady"],
+)];
+
 fn main() {
     println!("cargo:rerun-if-changed=src/wrapper.h");
     println!("cargo:rerun-if-changed=build.rs");
 
+    // the module functions are defined by notifications-core, use the checked-in bindings
+    if env::var_os("CARGO_FEATURE_MOCK").is_some() {
+        return;
+    }
+
     let link_search_path = "cargo:rustc-link-search=native";
     let link_lib = "cargo:rustc-link-lib=static";
 
@@ -38,7 +51,17 @@ fn main() {
     println!("{link_lib}=notifications");
     println!("{link_lib}=stdc++");
 
-    let bindings = bindgen::Builder::default()
+    // functions missing in the minimum module API level are not generated, so calling them is a
+    // compile error instead of a runtime `UnsupportedCommand`
+    let blocklist = API_LEVELS
+        .iter()
+        .filter(|(feature, _)| env::var_os(feature).is_none())
+        .flat_map(|(_, functions)| functions.iter().copied());
+
+    let bindings = blocklist
+        .fold(bindgen::Builder::default(), |builder, function| {
+            builder.blocklist_function(function)
+        })
         .use_core()
         .header("src/wrapper.h")
         .emit_builtins()
@@ -62,13 +85,11 @@ fn main() {
             &format!("-I{ppc}/powerpc-eabi/include/c++/{version}/powerpc-eabi"),
         ])
         .allowlist_file(".*/wums/include/notifications/.*.h")
-        .raw_line("#![allow(non_upper_case_globals)]")
-        .raw_line("#![allow(non_camel_case_types)]")
-        .raw_line("#![allow(non_snake_case)]")
         .generate()
         .expect("Unable to generate bindings");
 
-    let out = std::path::PathBuf::from("./src/bindings.rs");
+    // generated per build, since the blocklist depends on the features
+    let out = std::path::PathBuf::from(env::var("OUT_DIR").unwrap()).join("bindings.rs");
     bindings
         .write_to_file(&out)
         .expect("Unable to write bindings to file");
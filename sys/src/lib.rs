@@ -1,4 +1,52 @@
 #![no_std]
 
+#[cfg(not(feature = "mock"))]
+#[allow(non_upper_case_globals, non_camel_case_types, non_snake_case)]
+mod bindings {
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+// checked-in bindings of all functions, the mock defines the ones of the selected API level.
+#[cfg(feature = "mock")]
 mod bindings;
 pub use bindings::*;
+
+/// Name of a `NotificationModuleStatus` constant, e.g. `"NOTIFICATION_MODULE_RESULT_INVALID_HANDLE"`.
+///
+/// Unlike `NotificationModule_GetStatusStr` this doesn't need the module and the names never
+/// change, unknown values map to `"NOTIFICATION_MODULE_RESULT_UNKNOWN"`.
+pub const fn status_str(status: NotificationModuleStatus::Type) -> &'static str {
+    use NotificationModuleStatus as S;
+    match status {
+        S::NOTIFICATION_MODULE_RESULT_SUCCESS => "NOTIFICATION_MODULE_RESULT_SUCCESS",
+        S::NOTIFICATION_MODULE_RESULT_MODULE_NOT_FOUND => {
+            "NOTIFICATION_MODULE_RESULT_MODULE_NOT_FOUND"
+        }
+        S::NOTIFICATION_MODULE_RESULT_MODULE_MISSING_EXPORT => {
+            "NOTIFICATION_MODULE_RESULT_MODULE_MISSING_EXPORT"
+        }
+        S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_VERSION => {
+            "NOTIFICATION_MODULE_RESULT_UNSUPPORTED_VERSION"
+        }
+        S::NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT => {
+            "NOTIFICATION_MODULE_RESULT_INVALID_ARGUMENT"
+        }
+        S::NOTIFICATION_MODULE_RESULT_LIB_UNINITIALIZED => {
+            "NOTIFICATION_MODULE_RESULT_LIB_UNINITIALIZED"
+        }
+        S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_COMMAND => {
+            "NOTIFICATION_MODULE_RESULT_UNSUPPORTED_COMMAND"
+        }
+        S::NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY => {
+            "NOTIFICATION_MODULE_RESULT_OVERLAY_NOT_READY"
+        }
+        S::NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE => {
+            "NOTIFICATION_MODULE_RESULT_UNSUPPORTED_TYPE"
+        }
+        S::NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED => {
+            "NOTIFICATION_MODULE_RESULT_ALLOCATION_FAILED"
+        }
+        S::NOTIFICATION_MODULE_RESULT_INVALID_HANDLE => "NOTIFICATION_MODULE_RESULT_INVALID_HANDLE",
+        S::NOTIFICATION_MODULE_RESULT_UNKNOWN_ERROR => "NOTIFICATION_MODULE_RESULT_UNKNOWN_ERROR",
+        _ => "NOTIFICATION_MODULE_RESULT_UNKNOWN",
+    }
+}
@@ -0,0 +1,299 @@
+//! Versioned binary format for mirroring notifications to PC tooling, e.g. from a sink of the
+//! `notifications` crate sending over UDP or appending to a file.
+//!
+//! The crate has no dependencies, so decoders on a PC don't need the console toolchain.
+//!
+//! Every record is encoded as follows, integers are little endian:
+//!
+//! | Size      | Content                                                       |
+//! |-----------|---------------------------------------------------------------|
+//! | 2         | magic `b"WN"`                                                 |
+//! | 1         | format [`VERSION`]                                            |
+//! | 1         | [`Kind`]: 0 info, 1 error, 2 dynamic                          |
+//! | 1         | flags: bit 0 text color present, bit 1 background color present |
+//! | 4         | text color as RGBA, only if present                           |
+//! | 4         | background color as RGBA, only if present                    |
+//! | 8         | timestamp in microseconds since the console booted            |
+//! | 2 + n     | text as length prefixed UTF-8                                 |
+//! | 1         | number of fields                                              |
+//! | 1 + n     | per field: key as length prefixed UTF-8                       |
+//! | 2 + n     | per field: value as length prefixed UTF-8                     |
+//!
+//! Texts longer than the length prefix allows are truncated. Decoders must reject records with
+//! a higher version, new versions only append to the format.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt, time::Duration};
+
+/// Current version of the format.
+pub const VERSION: u8 = 1;
+
+const MAGIC: &[u8; 2] = b"WN";
+
+const TEXT_COLOR: u8 = 1 << 0;
+const BACKGROUND_COLOR: u8 = 1 << 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Kind {
+    Info = 0,
+    Error = 1,
+    Dynamic = 2,
+}
+
+/// Mirrored notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub kind: Kind,
+    /// RGBA, `None` for the module default.
+    pub text_color: Option<[u8; 4]>,
+    /// RGBA, `None` for the module default.
+    pub background_color: Option<[u8; 4]>,
+    /// Time since the console booted.
+    pub timestamp: Duration,
+    pub text: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl Record {
+    /// Record of `text` with default colors, shown at `timestamp`.
+    pub fn new(kind: Kind, text: &str, timestamp: Duration) -> Self {
+        Self {
+            kind,
+            text_color: None,
+            background_color: None,
+            timestamp,
+            text: String::from(text),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Appends the encoded record to `out`.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(self.kind as u8);
+
+        let mut flags = 0;
+        if self.text_color.is_some() {
+            flags |= TEXT_COLOR;
+        }
+        if self.background_color.is_some() {
+            flags |= BACKGROUND_COLOR;
+        }
+        out.push(flags);
+        if let Some(color) = self.text_color {
+            out.extend_from_slice(&color);
+        }
+        if let Some(color) = self.background_color {
+            out.extend_from_slice(&color);
+        }
+
+        out.extend_from_slice(&(self.timestamp.as_micros() as u64).to_le_bytes());
+        put_str(out, &self.text, u16::MAX as usize);
+
+        let fields = &self.fields[..self.fields.len().min(u8::MAX as usize)];
+        out.push(fields.len() as u8);
+        for (key, value) in fields {
+            put_str(out, key, u8::MAX as usize);
+            put_str(out, value, u16::MAX as usize);
+        }
+    }
+
+    /// Decodes a record from the start of `bytes`, returns it with the number of bytes read.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        if reader.take(2)? != MAGIC {
+            return Err(DecodeError::Magic);
+        }
+        let version = reader.u8()?;
+        if version > VERSION {
+            return Err(DecodeError::Version(version));
+        }
+        let kind = match reader.u8()? {
+            0 => Kind::Info,
+            1 => Kind::Error,
+            2 => Kind::Dynamic,
+            kind => return Err(DecodeError::Kind(kind)),
+        };
+
+        let flags = reader.u8()?;
+        let text_color = if flags & TEXT_COLOR != 0 {
+            Some(reader.array()?)
+        } else {
+            None
+        };
+        let background_color = if flags & BACKGROUND_COLOR != 0 {
+            Some(reader.array()?)
+        } else {
+            None
+        };
+
+        let timestamp = Duration::from_micros(u64::from_le_bytes(reader.array()?));
+        let len = u16::from_le_bytes(reader.array()?) as usize;
+        let text = reader.str(len)?;
+
+        let count = reader.u8()?;
+        let mut fields = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let len = reader.u8()? as usize;
+            let key = reader.str(len)?;
+            let len = u16::from_le_bytes(reader.array()?) as usize;
+            fields.push((key, reader.str(len)?));
+        }
+
+        let record = Self {
+            kind,
+            text_color,
+            background_color,
+            timestamp,
+            text,
+            fields,
+        };
+        Ok((record, reader.offset))
+    }
+}
+
+/// Writes `text` with a length prefix of the size of `max`, truncated on a char boundary.
+fn put_str(out: &mut Vec<u8>, text: &str, max: usize) {
+    let mut len = text.len().min(max);
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    if max <= u8::MAX as usize {
+        out.push(len as u8);
+    } else {
+        out.extend_from_slice(&(len as u16).to_le_bytes());
+    }
+    out.extend_from_slice(&text.as_bytes()[..len]);
+}
+
+// region: Decoding
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    Magic,
+    Version(u8),
+    Kind(u8),
+    Truncated,
+    Utf8,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Magic => f.write_str("Missing magic bytes"),
+            Self::Version(version) => write!(f, "Unsupported version {version}"),
+            Self::Kind(kind) => write!(f, "Unknown kind {kind}"),
+            Self::Truncated => f.write_str("Record is truncated"),
+            Self::Utf8 => f.write_str("Text is not UTF-8"),
+        }
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + len)
+            .ok_or(DecodeError::Truncated)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn str(&mut self, len: usize) -> Result<String, DecodeError> {
+        let bytes = self.take(len)?;
+        core::str::from_utf8(bytes)
+            .map(String::from)
+            .map_err(|_| DecodeError::Utf8)
+    }
+}
+
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> Record {
+        Record {
+            kind: Kind::Error,
+            text_color: Some([255, 255, 255, 255]),
+            background_color: None,
+            timestamp: Duration::from_micros(1_234_567),
+            text: String::from("Save failed \u{2717}"),
+            fields: alloc::vec![(String::from("slot"), String::from("2"))],
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut bytes = Vec::new();
+        record().encode(&mut bytes);
+        Record {
+            kind: Kind::Info,
+            ..record()
+        }
+        .encode(&mut bytes);
+
+        let (first, read) = Record::decode(&bytes).unwrap();
+        assert_eq!(first, record());
+        let (second, len) = Record::decode(&bytes[read..]).unwrap();
+        assert_eq!(second.kind, Kind::Info);
+        assert_eq!(read + len, bytes.len());
+    }
+
+    #[test]
+    fn truncates_on_char_boundary() {
+        let mut record = record();
+        record.text = "\u{e9}".repeat(u16::MAX as usize);
+        let mut bytes = Vec::new();
+        record.encode(&mut bytes);
+
+        let (decoded, _) = Record::decode(&bytes).unwrap();
+        assert_eq!(decoded.text.len(), u16::MAX as usize - 1);
+    }
+
+    #[test]
+    fn rejects() {
+        let mut bytes = Vec::new();
+        record().encode(&mut bytes);
+
+        assert_eq!(Record::decode(b"XX"), Err(DecodeError::Magic));
+        assert_eq!(
+            Record::decode(&bytes[..bytes.len() - 1]),
+            Err(DecodeError::Truncated)
+        );
+        bytes[2] = VERSION + 1;
+        assert_eq!(
+            Record::decode(&bytes),
+            Err(DecodeError::Version(VERSION + 1))
+        );
+        bytes[2] = VERSION;
+        bytes[3] = 7;
+        assert_eq!(Record::decode(&bytes), Err(DecodeError::Kind(7)));
+    }
+}